@@ -0,0 +1,44 @@
+//! Checks world definition files for problems without starting a game.
+//!
+//! Usage: `check_world [world.json ...]`, defaulting to the shipped world.
+//! Exits with a non-zero status if any file has errors.
+
+use std::env;
+use std::process;
+use text_adventure::world_building::World;
+use text_adventure::DEFAULT_WORLD;
+
+fn main() {
+    let mut paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        paths.push(DEFAULT_WORLD.to_string());
+    }
+
+    let mut failed = false;
+    for path in paths {
+        match World::check_file(&path) {
+            Ok(problems) => {
+                for problem in &problems {
+                    let severity = if problem.is_warning() {
+                        "warning"
+                    } else {
+                        failed = true;
+                        "error"
+                    };
+                    println!("{}: {}: {}", path, severity, problem);
+                }
+                if problems.is_empty() {
+                    println!("{}: ok", path);
+                }
+            }
+            Err(err) => {
+                failed = true;
+                println!("error: {}", err);
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,41 @@
+//! Replays transcripts against a world and reports where the responses
+//! differ from what the transcript expects.
+//!
+//! Usage: `playthrough <world.json> <transcript> [transcript ...]`.
+//! Exits with a non-zero status if any transcript fails.
+
+use std::env;
+use std::process;
+use text_adventure::playthrough::Transcript;
+use text_adventure::world_building::World;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        println!("usage: playthrough <world.json> <transcript> [transcript ...]");
+        process::exit(2);
+    }
+
+    let mut failed = false;
+    for path in &args[1..] {
+        let result = Transcript::from_file(path).and_then(|transcript| {
+            World::from_file(&args[0])
+                .map(|world| transcript.run(world))
+                .map_err(|err| err.to_string())
+        });
+        match result {
+            Ok(report) => {
+                failed |= !report.passed();
+                println!("{}: {}", path, report);
+            }
+            Err(err) => {
+                failed = true;
+                println!("error: {}", err);
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -1,11 +1,45 @@
+pub mod container;
+pub mod exit;
 pub mod feature;
 pub mod interactive;
 pub mod item;
 pub mod player;
+pub mod properties;
 pub mod room;
 
 // Provide internal structs directly from entities.
+pub use container::Container;
+pub use exit::Exit;
 pub use feature::Feature;
 pub use item::Item;
 pub use player::Player;
+pub use properties::Properties;
 pub use room::Room;
+
+use crate::flags::{self, ConditionalText};
+use crate::world_building::World;
+
+/// Describes an item or feature when the player examines it, including
+/// anything inside it
+fn describe_object(
+    name: &str,
+    description: &Option<String>,
+    alternative_descriptions: &[ConditionalText],
+    container: &Option<Container>,
+    world: &World,
+) -> String {
+    let default = format!("You see nothing special about the {}", name);
+    let description = description.as_deref().unwrap_or(&default);
+    let mut output = flags::select_text(description, alternative_descriptions, world).to_string();
+
+    if let Some(container) = container {
+        if let Some(contents) = container.visible_contents() {
+            output.push_str(&format!("\nIt contains {}", contents));
+        }
+        if !container.open {
+            output.push_str("\nIt is closed");
+        }
+    }
+
+    output
+}
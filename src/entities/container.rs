@@ -0,0 +1,236 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::Item;
+use crate::errors::{GameError, GameResult};
+
+/// Lets an item or feature hold other items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Container {
+    /// The items inside
+    #[serde(default)]
+    pub contents: Vec<Item>,
+
+    /// Whether the container is open, so that things can be put in or taken
+    /// out
+    #[serde(default = "open_by_default")]
+    pub open: bool,
+
+    /// Whether the player can open and close the container
+    #[serde(default)]
+    pub openable: bool,
+
+    /// Whether the container is locked shut
+    #[serde(default)]
+    pub locked: bool,
+
+    /// The id of the item that locks and unlocks the container
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// Whether the contents can be seen while the container is closed
+    #[serde(default)]
+    pub transparent: bool,
+
+    /// The most items the container can hold, or no limit if not given
+    #[serde(default)]
+    pub capacity: Option<usize>,
+}
+
+fn open_by_default() -> bool {
+    true
+}
+
+impl Default for Container {
+    /// An open container without a lid or limit
+    fn default() -> Container {
+        Container {
+            contents: Vec::new(),
+            open: true,
+            openable: false,
+            locked: false,
+            key: None,
+            transparent: false,
+            capacity: None,
+        }
+    }
+}
+
+impl Container {
+    /// Places an item inside the container
+    pub fn add_item(&mut self, item: Item) {
+        self.contents.push(item);
+    }
+
+    /// Gives the items inside ids based on their names where the world
+    /// definition left them out
+    pub fn assign_default_ids(&mut self) {
+        for item in &mut self.contents {
+            item.assign_default_ids();
+        }
+    }
+
+    /// Returns true if the player can see what is inside
+    pub fn shows_contents(&self) -> bool {
+        self.open || self.transparent
+    }
+
+    /// Lists what can be seen inside, e.g. "coin, gem", or `None` if nothing
+    /// can. Hidden items are left out.
+    pub fn visible_contents(&self) -> Option<String> {
+        let mut visible = self
+            .contents
+            .iter()
+            .filter(|item| !item.properties.hidden)
+            .peekable();
+        if self.shows_contents() && visible.peek().is_some() {
+            Some(visible.map(|item| &item.name).join(", "))
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the container is open and has room for another item
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the container, for messages
+    ///
+    /// # Errors
+    /// The container is closed or full
+    pub fn accepts(&self, name: &str) -> GameResult<()> {
+        if !self.open {
+            return Err(GameError::Refused(format!("The {} is closed", name)));
+        }
+        if self
+            .capacity
+            .is_some_and(|capacity| self.contents.len() >= capacity)
+        {
+            return Err(GameError::Refused(format!("The {} is full", name)));
+        }
+        Ok(())
+    }
+
+    /// Takes an item out of the container
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the container, for messages
+    /// * `item_id` - the id of the item to take out
+    ///
+    /// # Errors
+    /// The container is closed or does not hold the item where it can be
+    /// seen
+    pub fn remove(&mut self, name: &str, item_id: &str) -> GameResult<Item> {
+        if !self.open {
+            return Err(GameError::Refused(format!("The {} is closed", name)));
+        }
+        let position = self
+            .contents
+            .iter()
+            .position(|item| item.id == item_id && !item.properties.hidden);
+        match position {
+            Some(index) => Ok(self.contents.remove(index)),
+            None => Err(GameError::Refused(format!(
+                "There is no {} in the {}",
+                item_id, name
+            ))),
+        }
+    }
+
+    /// Opens the container, saying what it reveals
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the container, for messages
+    ///
+    /// # Errors
+    /// The container cannot be opened, is locked or is already open
+    pub fn open(&mut self, name: &str) -> GameResult<String> {
+        if !self.openable {
+            return Err(GameError::Refused(format!("You cannot open the {}", name)));
+        }
+        if self.open {
+            return Err(GameError::Refused(format!("The {} is already open", name)));
+        }
+        if self.locked {
+            return Err(GameError::Refused(format!("The {} is locked", name)));
+        }
+
+        self.open = true;
+        Ok(match self.visible_contents() {
+            Some(contents) => format!("You open the {}, revealing {}", name, contents),
+            None => format!("You open the {}", name),
+        })
+    }
+
+    /// Closes the container
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the container, for messages
+    ///
+    /// # Errors
+    /// The container cannot be closed or is already closed
+    pub fn close(&mut self, name: &str) -> GameResult<String> {
+        if !self.openable {
+            return Err(GameError::Refused(format!("You cannot close the {}", name)));
+        }
+        if !self.open {
+            return Err(GameError::Refused(format!(
+                "The {} is already closed",
+                name
+            )));
+        }
+
+        self.open = false;
+        Ok(format!("You close the {}", name))
+    }
+
+    /// Locks or unlocks the container with a key
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the container, for messages
+    /// * `key_id` - the id of the item used as the key
+    /// * `key_name` - the name of the item used as the key
+    /// * `lock` - true to lock the container, false to unlock it
+    ///
+    /// # Errors
+    /// The item is not the container's key, the container is open, or it is
+    /// already locked or unlocked
+    pub fn set_locked(
+        &mut self,
+        name: &str,
+        key_id: &str,
+        key_name: &str,
+        lock: bool,
+    ) -> GameResult<String> {
+        let (verb, state) = if lock {
+            ("lock", "locked")
+        } else {
+            ("unlock", "unlocked")
+        };
+        if self.key.as_deref() != Some(key_id) {
+            return Err(GameError::Refused(format!(
+                "The {} does not fit the {}",
+                key_name, name
+            )));
+        }
+        if self.locked == lock {
+            return Err(GameError::Refused(format!(
+                "The {} is already {}",
+                name, state
+            )));
+        }
+        if lock && self.open {
+            return Err(GameError::Refused(format!(
+                "You must close the {} first",
+                name
+            )));
+        }
+
+        self.locked = lock;
+        Ok(format!("You {} the {} with the {}", verb, name, key_name))
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+use crate::errors::{GameError, GameResult};
+use crate::flags::{self, Condition};
+use crate::world_building::World;
+
+/// A way out of a room, which may be locked or only passable when its
+/// conditions hold
+#[derive(Debug, Clone, new, Serialize, Deserialize)]
+pub struct Exit {
+    /// The id of the Room the exit leads to
+    pub destination: String,
+
+    /// The conditions that must all hold for the player to pass
+    #[new(default)]
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+
+    /// Whether the exit is locked, see `key`
+    #[new(default)]
+    #[serde(default)]
+    pub locked: bool,
+
+    /// The id of the item that locks and unlocks the exit
+    #[new(default)]
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// The id of the feature the key is used on, e.g. 'north door'
+    #[new(default)]
+    #[serde(default)]
+    pub door: Option<String>,
+
+    /// The text shown when the player cannot pass
+    #[new(default)]
+    #[serde(default)]
+    pub refusal: Option<String>,
+}
+
+impl Exit {
+    /// Checks if the player can pass through the exit
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - the direction of the exit, used in the default refusal
+    /// * `world` - the world the exit's conditions are tested against
+    ///
+    /// # Errors
+    /// The exit is locked or its conditions do not hold
+    pub fn check_passable(&self, direction: &str, world: &World) -> GameResult<()> {
+        if self.locked {
+            Err(self.refusal_or(format!("The way {} is locked", direction)))
+        } else if !flags::all_hold(&self.conditions, world) {
+            Err(self.refusal_or(format!("You cannot go {} yet", direction)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks if the item is the key for the exit, used on its door or on
+    /// the direction itself
+    pub fn is_unlocked_by(&self, direction: &str, item_id: &str, target: &str) -> bool {
+        let key_matches = self.key.as_deref() == Some(item_id);
+        let target_matches =
+            direction.eq_ignore_ascii_case(target) || self.door.as_deref() == Some(target);
+        key_matches && target_matches
+    }
+
+    fn refusal_or(&self, default: String) -> GameError {
+        GameError::Refused(self.refusal.clone().unwrap_or(default))
+    }
+}
+
+/// Reads the exits of a room, where an exit that is always passable can be
+/// written as just the id of its destination. Directions are stored in lower
+/// case to match the player's commands.
+pub fn deserialize_exits<'de, D>(deserializer: D) -> Result<HashMap<String, Exit>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ExitDefinition {
+        Destination(String),
+        Exit(Exit),
+    }
+
+    let definitions = HashMap::<String, ExitDefinition>::deserialize(deserializer)?;
+    Ok(definitions
+        .into_iter()
+        .map(|(direction, definition)| {
+            let exit = match definition {
+                ExitDefinition::Destination(destination) => Exit::new(destination),
+                ExitDefinition::Exit(exit) => exit,
+            };
+            (direction.to_lowercase(), exit)
+        })
+        .collect())
+}
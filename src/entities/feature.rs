@@ -1,30 +1,91 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
+
+use crate::entities::{Container, Properties};
+use crate::flags::ConditionalText;
+use crate::resolver::Noun;
+use crate::world_building::World;
 
 /// Describes an attribute of a room, which will eventually hold behaviour
-#[derive(Debug, new, Serialize, Deserialize)]
+#[derive(Debug, Clone, new, Serialize, Deserialize)]
 pub struct Feature {
+    /// Identifies the feature in rules, exits and saves, defaulting to its
+    /// name in lower case
+    #[new(value = "name.to_lowercase()")]
+    #[serde(default)]
+    pub id: String,
+
+    /// The name shown to the player
     pub name: String,
+
+    /// Other names the player can call the feature
+    #[new(default)]
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Words the player can use to pick out the feature, e.g. "oak"
+    #[new(default)]
+    #[serde(default)]
+    pub adjectives: Vec<String>,
+
+    /// The text shown when the player examines the feature
+    #[new(default)]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Descriptions that replace the default one when their conditions hold
+    #[new(default)]
+    #[serde(default)]
+    pub alternative_descriptions: Vec<ConditionalText>,
+
+    /// Present if items can be put inside the feature
+    #[new(default)]
+    #[serde(default)]
+    pub container: Option<Container>,
+
+    /// Whether the feature can be taken, seen and listed
+    #[new(default)]
+    #[serde(flatten)]
+    pub properties: Properties,
 }
 
-impl<'a> Feature {
+impl Feature {
+    /// The words the player can use to refer to the feature
+    pub fn noun(&self) -> Noun {
+        Noun::new(
+            self.id.clone(),
+            self.name.clone(),
+            self.aliases.clone(),
+            self.adjectives.clone(),
+        )
+    }
+
+    /// Gives the feature, and anything inside it, an id based on its name where
+    /// the world definition left it out
+    pub fn assign_default_ids(&mut self) {
+        if self.id.is_empty() {
+            self.id = self.name.to_lowercase();
+        }
+        if let Some(container) = &mut self.container {
+            container.assign_default_ids();
+        }
+    }
+
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
 
-    pub fn save_state(&self) -> Result<String, String> {
-        match serde_json::to_string(self) {
-            Ok(json) => match fs::write("savedata.json", json) {
-                Ok(_msg) => Ok("game saved".to_string()),
-                Err(err) => {
-                    error!("Error saving game {:?}", err);
-                    Err("could not save game".to_string())
-                }
-            },
-            Err(err) => {
-                error!("Error serializing game state {:?}", err);
-                Err("could not save game".to_string())
-            }
-        }
+    /// Describes the feature when the player examines it
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world whose flags decide which description is used
+    pub fn get_description(&self, world: &World) -> String {
+        super::describe_object(
+            &self.name,
+            &self.description,
+            &self.alternative_descriptions,
+            &self.container,
+            world,
+        )
     }
 }
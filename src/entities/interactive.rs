@@ -1,7 +1,212 @@
-use crate::entities::{Feature, Item, Room};
+use serde::{Deserialize, Serialize};
 
-pub fn use_item(item: &Item, feature: &mut Feature, room: &mut Room) -> Result<String, String> {
-    room.remove_feature(feature.get_name());
-    room.add_exit("up".to_string(), "hello".to_string());
-    Ok("created door".to_owned())
+use crate::entities::{Feature, Item};
+use crate::errors::{GameError, GameResult};
+use crate::flags::{Condition, FlagValue};
+use crate::world_building::World;
+
+/// Describes what happens when the player uses an item on a feature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    /// The verbs that trigger the rule, e.g. "light" for "light torch with
+    /// tinderbox"
+    #[serde(default = "use_verb")]
+    pub verbs: Vec<String>,
+
+    /// The id of the item being used
+    pub item: String,
+
+    /// The id of the feature the item is used on
+    pub feature: String,
+
+    /// The id of the room the rule applies in, or any room if not given
+    #[serde(default)]
+    pub room: Option<String>,
+
+    /// The conditions that must all hold for the rule to apply
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+
+    /// The text shown to the player when the rule is triggered
+    pub message: String,
+
+    /// The changes made to the world, in order
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+/// A change to the world made by a `Rule`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    /// Adds an exit to the room
+    OpenExit {
+        /// The direction of the new exit
+        direction: String,
+        /// The id of the room the exit leads to
+        destination: String,
+    },
+    /// Unlocks the exit of the room in a direction
+    UnlockExit(String),
+    /// Locks the exit of the room in a direction
+    LockExit(String),
+    /// Removes a feature from the room, by id
+    RemoveFeature(String),
+    /// Swaps a feature of the room for another
+    ReplaceFeature {
+        /// The id of the feature to remove
+        feature: String,
+        /// The feature that takes its place
+        with: Feature,
+    },
+    /// Places a new item in the room
+    SpawnItem(Item),
+    /// Removes the item that was used from the player's inventory
+    ConsumeItem,
+    /// Makes a hidden item or feature of the room visible, by id
+    Reveal(String),
+    /// Adds a line to the text shown to the player
+    Print(String),
+    /// Sets a flag to true
+    SetFlag(String),
+    /// Returns a flag to its unset state
+    ClearFlag(String),
+    /// Sets a flag to any value
+    SetValue {
+        /// The name of the flag
+        flag: String,
+        /// Its new value
+        value: FlagValue,
+    },
+    /// Adds to a counter
+    Increment {
+        /// The name of the counter
+        flag: String,
+        /// The amount to add, which may be negative
+        #[serde(default = "one")]
+        amount: i64,
+    },
+}
+
+fn one() -> i64 {
+    1
+}
+
+fn use_verb() -> Vec<String> {
+    vec!["use".to_string()]
+}
+
+impl Rule {
+    /// Checks if the rule covers using the item on the feature in the room
+    /// with the verb
+    pub fn matches(&self, verb: &str, item_id: &str, feature_id: &str, room_id: &str) -> bool {
+        self.verbs.iter().any(|v| v.eq_ignore_ascii_case(verb))
+            && self.item == item_id
+            && self.feature == feature_id
+            && self.room.as_ref().is_none_or(|room| room == room_id)
+    }
+
+    /// Gives the items and features the rule creates ids based on their
+    /// names where the world definition left them out
+    pub fn assign_default_ids(&mut self) {
+        for effect in &mut self.effects {
+            match effect {
+                Effect::SpawnItem(item) => item.assign_default_ids(),
+                Effect::ReplaceFeature { with, .. } => with.assign_default_ids(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Describes the rule for error messages
+    pub fn label(&self) -> String {
+        format!("rule using '{}' on '{}'", self.item, self.feature)
+    }
+}
+
+/// Apply the effects of a rule to the world, returning the text to show
+/// the player. Effects that no longer make sense, such as removing a feature
+/// that is already gone, are logged and skipped.
+///
+/// # Arguments
+///
+/// * `rule` - the rule that was triggered
+/// * `world` - the world to change
+pub fn apply_rule(rule: &Rule, world: &mut World) -> String {
+    let mut output = rule.message.clone();
+
+    for effect in &rule.effects {
+        if let Effect::Print(text) = effect {
+            output.push('\n');
+            output.push_str(text);
+            continue;
+        }
+        if let Err(err) = apply_effect(rule, effect, world) {
+            warn!("{}: {}", rule.label(), err);
+        }
+    }
+
+    output
+}
+
+fn apply_effect(rule: &Rule, effect: &Effect, world: &mut World) -> GameResult<String> {
+    match effect {
+        Effect::OpenExit {
+            direction,
+            destination,
+        } => {
+            world
+                .current_room_mut()?
+                .add_exit(direction.clone(), destination.clone());
+            Ok(direction.clone())
+        }
+        Effect::UnlockExit(direction) => set_exit_locked(world, direction, false),
+        Effect::LockExit(direction) => set_exit_locked(world, direction, true),
+        Effect::RemoveFeature(feature_id) => world.current_room_mut()?.remove_feature(feature_id),
+        Effect::ReplaceFeature { feature, with } => {
+            let room = world.current_room_mut()?;
+            let removed = room.remove_feature(feature)?;
+            room.add_feature(with.clone());
+            Ok(removed)
+        }
+        Effect::SpawnItem(item) => {
+            world.current_room_mut()?.add_item(item.clone());
+            Ok(item.name.clone())
+        }
+        Effect::ConsumeItem => {
+            let inventory = &mut world.player.inventory;
+            match inventory.iter().position(|i| i.id == rule.item) {
+                Some(index) => Ok(inventory.remove(index).name),
+                None => Err(GameError::NotCarrying(rule.item.clone())),
+            }
+        }
+        Effect::Reveal(id) => world.current_room_mut()?.reveal(id),
+        Effect::Print(text) => Ok(text.clone()),
+        Effect::SetFlag(flag) => {
+            world.flags.set(flag, FlagValue::Bool(true));
+            Ok(flag.clone())
+        }
+        Effect::ClearFlag(flag) => {
+            world.flags.clear(flag);
+            Ok(flag.clone())
+        }
+        Effect::SetValue { flag, value } => {
+            world.flags.set(flag, value.clone());
+            Ok(flag.clone())
+        }
+        Effect::Increment { flag, amount } => {
+            world.flags.add(flag, *amount);
+            Ok(flag.clone())
+        }
+    }
+}
+
+fn set_exit_locked(world: &mut World, direction: &str, locked: bool) -> GameResult<String> {
+    match world.current_room_mut()?.exits.get_mut(direction) {
+        Some(exit) => {
+            exit.locked = locked;
+            Ok(direction.to_string())
+        }
+        None => Err(GameError::UnknownDirection(direction.to_string())),
+    }
 }
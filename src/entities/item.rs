@@ -1,8 +1,109 @@
 use serde::{Deserialize, Serialize};
 
+use crate::entities::{Container, Properties};
+use crate::flags::ConditionalText;
+use crate::resolver::Noun;
+use crate::world_building::World;
+
 /// An item
-#[derive(Debug, new, Serialize, Deserialize)]
+#[derive(Debug, Clone, new, Serialize, Deserialize)]
 pub struct Item {
-    /// The item name
+    /// Identifies the item in rules, exits and saves, defaulting to its name
+    /// in lower case
+    #[new(value = "name.to_lowercase()")]
+    #[serde(default)]
+    pub id: String,
+
+    /// The name shown to the player
     pub name: String,
+
+    /// Other names the player can call the item
+    #[new(default)]
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Words the player can use to pick out the item, e.g. "rusty"
+    #[new(default)]
+    #[serde(default)]
+    pub adjectives: Vec<String>,
+
+    /// The text shown when the player examines the item
+    #[new(default)]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Descriptions that replace the default one when their conditions hold
+    #[new(default)]
+    #[serde(default)]
+    pub alternative_descriptions: Vec<ConditionalText>,
+
+    /// Present if other items can be put inside this one
+    #[new(default)]
+    #[serde(default)]
+    pub container: Option<Container>,
+
+    /// Whether the item can be taken, seen and listed
+    #[new(default)]
+    #[serde(flatten)]
+    pub properties: Properties,
+
+    /// How heavy the item is, not counting anything inside it
+    #[new(value = "1")]
+    #[serde(default = "one")]
+    pub weight: u32,
+
+    /// How much room the item takes up among the things the player carries
+    #[new(value = "1")]
+    #[serde(default = "one")]
+    pub bulk: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl Item {
+    /// The words the player can use to refer to the item
+    pub fn noun(&self) -> Noun {
+        Noun::new(
+            self.id.clone(),
+            self.name.clone(),
+            self.aliases.clone(),
+            self.adjectives.clone(),
+        )
+    }
+
+    /// Gives the item, and anything inside it, an id based on its name where
+    /// the world definition left it out
+    pub fn assign_default_ids(&mut self) {
+        if self.id.is_empty() {
+            self.id = self.name.to_lowercase();
+        }
+        if let Some(container) = &mut self.container {
+            container.assign_default_ids();
+        }
+    }
+
+    /// The weight of the item together with everything inside it
+    pub fn total_weight(&self) -> u32 {
+        self.weight
+            + self.container.as_ref().map_or(0, |container| {
+                container.contents.iter().map(Item::total_weight).sum()
+            })
+    }
+
+    /// Describes the item when the player examines it
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world whose flags decide which description is used
+    pub fn get_description(&self, world: &World) -> String {
+        super::describe_object(
+            &self.name,
+            &self.description,
+            &self.alternative_descriptions,
+            &self.container,
+            world,
+        )
+    }
 }
@@ -1,32 +1,107 @@
 use serde::{Deserialize, Serialize};
 
 use crate::entities::Item;
+use crate::errors::{GameError, GameResult};
 
 /// Represents a player
-#[derive(Default, Debug, new, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, new, Serialize, Deserialize)]
 pub struct Player {
     /// The player name
     pub name: String,
 
     /// The items the player is carrying
     #[new(default)]
+    #[serde(default)]
     pub inventory: Vec<Item>,
+
+    /// The most weight the player can carry, or no limit if not given
+    #[new(default)]
+    #[serde(default)]
+    pub max_weight: Option<u32>,
+
+    /// The most bulk the player can carry, or no limit if not given
+    #[new(default)]
+    #[serde(default)]
+    pub max_bulk: Option<u32>,
 }
 
 /// Lists the inventory currently carried by the player
 impl Player {
-    /// List the items carried by the player
+    /// List the items carried by the player, followed by how much of their
+    /// carrying capacity is used where it is limited
     pub fn list_inventory(&self) -> String {
-        self.inventory.iter().fold(String::new(), |mut agg, item| {
-            agg.push_str(&*item.name);
-            agg.push_str("\n");
+        let mut output = self.inventory.iter().fold(String::new(), |mut agg, item| {
+            agg.push_str(&item.name);
+            agg.push('\n');
             agg
-        })
+        });
+        if let Some(max_weight) = self.max_weight {
+            output.push_str(&format!("Weight: {} of {}\n", self.weight(), max_weight));
+        }
+        if let Some(max_bulk) = self.max_bulk {
+            output.push_str(&format!("Bulk: {} of {}\n", self.bulk(), max_bulk));
+        }
+        output
+    }
+
+    /// The total weight of the items carried, including their contents
+    pub fn weight(&self) -> u32 {
+        self.inventory.iter().map(Item::total_weight).sum()
+    }
+
+    /// The total bulk of the items carried
+    pub fn bulk(&self) -> u32 {
+        self.inventory.iter().map(|item| item.bulk).sum()
+    }
+
+    /// Checks that the player can pick up an item on top of what they carry
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - the item to pick up
+    /// * `carried` - whether the item is already carried inside a container,
+    ///   so that its weight counts towards the load already
+    ///
+    /// # Errors
+    /// The item would take the player over their weight or bulk limit
+    pub fn can_carry(&self, item: &Item, carried: bool) -> GameResult<()> {
+        let load = if carried {
+            self.weight().saturating_sub(item.total_weight())
+        } else {
+            self.weight()
+        };
+        if self
+            .max_weight
+            .is_some_and(|max_weight| load + item.total_weight() > max_weight)
+        {
+            return Err(GameError::Refused(format!(
+                "The {} is too heavy to carry as well as everything else",
+                item.name
+            )));
+        }
+        if self
+            .max_bulk
+            .is_some_and(|max_bulk| self.bulk() + item.bulk > max_bulk)
+        {
+            return Err(GameError::Refused(format!(
+                "Your hands are full, you cannot carry the {} as well",
+                item.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks if the player has the item with that id
+    pub fn has_item(&self, item_id: &str) -> bool {
+        self.inventory.iter().any(|i| i.id == item_id)
     }
 
-    /// Checks if the player has an item of that name
-    pub fn has_item(&self, item_name: &str) -> bool {
-        let lower = item_name.to_lowercase();
-        self.inventory.iter().any(|i| i.name == lower)
+    /// Gets the name of a carried item, or the id if the player does not
+    /// have it
+    pub fn item_name(&self, item_id: &str) -> String {
+        self.inventory
+            .iter()
+            .find(|i| i.id == item_id)
+            .map_or_else(|| item_id.to_string(), |i| i.name.clone())
     }
 }
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::GameError;
+
+/// Decides what the player can do with an item or feature
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Properties {
+    /// Whether the object is fixed in place, so that it cannot be taken.
+    /// Features are always fixed, items only when this is set
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fixed: bool,
+
+    /// Whether the object is left out of descriptions and cannot be referred
+    /// to until a rule reveals it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hidden: bool,
+
+    /// Whether the object is part of the scenery, which the room description
+    /// already mentions. Scenery is not listed and cannot be taken, but can
+    /// be examined
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub scenery: bool,
+
+    /// The text shown when the player tries to take the object, e.g. "The
+    /// statue is far too heavy"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Properties {
+    /// Returns true if the player can pick the object up
+    pub fn is_portable(&self) -> bool {
+        !self.fixed && !self.scenery
+    }
+
+    /// Returns true if the object is listed in room descriptions
+    pub fn is_listed(&self) -> bool {
+        !self.hidden && !self.scenery
+    }
+
+    /// The error given when the player tries to take the object
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the object, for the default message
+    pub fn refuse_take(&self, name: &str) -> GameError {
+        GameError::Refused(
+            self.refusal
+                .clone()
+                .unwrap_or_else(|| format!("You cannot take the {}", name)),
+        )
+    }
+}
@@ -2,33 +2,52 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::entities::exit::{self, Exit};
 use crate::entities::{Feature, Item, Player};
+use crate::errors::{GameError, GameResult};
+use crate::flags::{self, ConditionalText};
+use crate::world_building::World;
 
 /// Describes a location and its contents
-#[derive(Debug, new, Serialize, Deserialize)]
+#[derive(Debug, Clone, new, Serialize, Deserialize)]
 pub struct Room {
     /// Unique ID of the Room
     pub id: String,
 
+    /// The name of the Room shown to the player, defaulting to its id
+    #[new(default)]
+    #[serde(default)]
+    pub name: String,
+
     /// The description of the Room the player will see
     pub description: String,
+
+    /// Descriptions that replace the default one when their conditions hold
     #[new(default)]
-    pub exits: HashMap<String, String>,
+    #[serde(default)]
+    pub alternative_descriptions: Vec<ConditionalText>,
+    /// The ways out of the Room, stored by direction
     #[new(default)]
+    #[serde(default, deserialize_with = "exit::deserialize_exits")]
+    pub exits: HashMap<String, Exit>,
+    #[new(default)]
+    #[serde(default)]
     pub items: Vec<Item>,
     #[new(default)]
+    #[serde(default)]
     pub features: Vec<Feature>,
 }
 
-impl<'a> Room {
-    /// Add an exit to the room
+impl Room {
+    /// Add an exit to the room that is always passable
     ///
     /// # Arguments
     ///
     /// * `direction` - the name of the direction e.g. 'North'
     /// * `exit_id` - the name of the exit, must match the id of a Room.
     pub fn add_exit(&mut self, direction: String, exit_id: String) {
-        self.exits.insert(direction, exit_id);
+        self.exits
+            .insert(direction.to_lowercase(), Exit::new(exit_id));
     }
 
     /// Gets the directions of all the exits
@@ -46,17 +65,54 @@ impl<'a> Room {
         self.exits.contains_key(&*lower)
     }
 
-    /// Checks if the room has a feature
-    pub fn has_feature(&self, feature_name: &str) -> bool {
-        let lower = feature_name.to_lowercase();
-        self.features.iter().any(|f| f.name == lower)
+    /// The name of the Room shown to the player
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    /// Checks if the room has the feature with that id
+    pub fn has_feature(&self, feature_id: &str) -> bool {
+        self.features.iter().any(|f| f.id == feature_id)
     }
+
+    /// Gets the name of a feature of the room, or the id if there is no such
+    /// feature
+    pub fn feature_name(&self, feature_id: &str) -> String {
+        self.features
+            .iter()
+            .find(|f| f.id == feature_id)
+            .map_or_else(|| feature_id.to_string(), |f| f.name.clone())
+    }
+
+    /// Gives the room's items and features, and anything inside them, ids
+    /// based on their names where the world definition left them out
+    pub fn assign_default_ids(&mut self) {
+        for item in &mut self.items {
+            item.assign_default_ids();
+        }
+        for feature in &mut self.features {
+            feature.assign_default_ids();
+        }
+    }
+
+    /// Gets the names of the items listed in the room's description
     pub fn get_item_names(&self) -> impl Iterator<Item = String> + '_ {
-        self.items.iter().map(|i| i.name.to_string())
+        self.items
+            .iter()
+            .filter(|i| i.properties.is_listed())
+            .map(|i| i.name.to_string())
     }
 
+    /// Gets the names of the features listed in the room's description
     pub fn get_feature_names(&self) -> impl Iterator<Item = String> + '_ {
-        self.features.iter().map(|i| i.name.to_string())
+        self.features
+            .iter()
+            .filter(|f| f.properties.is_listed())
+            .map(|f| f.name.to_string())
     }
 
     /// Adds the item to the room's inventory
@@ -68,25 +124,33 @@ impl<'a> Room {
         self.items.push(item);
     }
 
-    /// Returns true if the room has any items, false otherwise.
+    /// Returns true if the room has any items to list, false otherwise.
     pub fn has_items(&self) -> bool {
-        self.items.len() > 0
+        self.get_item_names().next().is_some()
     }
 
     /// Creates a complete description of the location including
     /// exits and items.
-    pub fn get_full_description(&self) -> String {
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world whose flags decide which description is used
+    pub fn get_full_description(&self, world: &World) -> String {
         let mut output = String::new();
         let description = format!(
             "\n{location_description}",
-            location_description = self.description
+            location_description =
+                flags::select_text(&self.description, &self.alternative_descriptions, world)
         );
         output.push_str(&description);
 
-        let exits = format!("\nExits are {exits}", exits = self.get_exits().join(", "));
+        let exits = format!(
+            "\nExits are {exits}",
+            exits = self.get_exits().sorted().join(", ")
+        );
         output.push_str(&exits);
 
-        if self.features.len() > 0 {
+        if self.get_feature_names().next().is_some() {
             let features = format!(
                 "\nThere is {feature_names}",
                 feature_names = self.get_feature_names().join(", ")
@@ -102,6 +166,23 @@ impl<'a> Room {
             output.push_str(&items);
         }
 
+        let containers = self
+            .features
+            .iter()
+            .filter(|f| !f.properties.hidden)
+            .map(|f| (&f.name, &f.container))
+            .chain(
+                self.items
+                    .iter()
+                    .filter(|i| !i.properties.hidden)
+                    .map(|i| (&i.name, &i.container)),
+            );
+        for (name, container) in containers {
+            if let Some(contents) = container.as_ref().and_then(|c| c.visible_contents()) {
+                output.push_str(&format!("\nThe {} contains {}", name, contents));
+            }
+        }
+
         output
     }
 
@@ -110,18 +191,37 @@ impl<'a> Room {
     /// # Arguments
     ///
     /// * `player` - The player who will receive the item
-    /// * `item_name` - The name of the item to be take
+    /// * `item_id` - The id of the item to be taken
     ///
     /// # Errors
-    /// The item does not exist in the player's current location
-    pub fn take_item(&mut self, player: &mut Player, item_name: String) -> Result<String, String> {
-        match self.items.iter().position(|i| i.name == item_name) {
+    /// The item does not exist in the player's current location, is fixed in
+    /// place, or is too much for the player to carry
+    pub fn take_item(&mut self, player: &mut Player, item_id: &str) -> GameResult<String> {
+        if let Some(feature) = self
+            .features
+            .iter()
+            .find(|f| f.id == item_id && !f.properties.hidden)
+        {
+            return Err(feature.properties.refuse_take(&feature.name));
+        }
+
+        let position = self
+            .items
+            .iter()
+            .position(|i| i.id == item_id && !i.properties.hidden);
+        match position {
             Some(index) => {
+                let item = &self.items[index];
+                if !item.properties.is_portable() {
+                    return Err(item.properties.refuse_take(&item.name));
+                }
+                player.can_carry(item, false)?;
                 let temp = self.items.remove(index);
+                let message = format!("Picked up {}", temp.name);
                 player.inventory.push(temp);
-                Ok(format!("Picked up {}", item_name))
+                Ok(message)
             }
-            None => Err(format!("No item of type {} is present", item_name)),
+            None => Err(GameError::MissingItem(item_id.to_string())),
         }
     }
 
@@ -129,13 +229,50 @@ impl<'a> Room {
         self.features.push(feature);
     }
 
-    pub fn remove_feature(&mut self, feature_name: String) -> Result<String, String> {
-        match self.features.iter().position(|i| i.name == feature_name) {
-            Some(index) => {
-                self.features.remove(index);
-                Ok(feature_name)
+    /// Makes a hidden item or feature of the room, or an item inside one of
+    /// its containers, visible, returning its name
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the item or feature to reveal
+    ///
+    /// # Errors
+    /// The room has no item or feature with that id
+    pub fn reveal(&mut self, id: &str) -> GameResult<String> {
+        for feature in &mut self.features {
+            if feature.id == id {
+                feature.properties.hidden = false;
+                return Ok(feature.name.clone());
+            }
+            if let Some(name) = feature
+                .container
+                .as_mut()
+                .and_then(|container| reveal_item(&mut container.contents, id))
+            {
+                return Ok(name);
             }
-            None => Err(format!("No feature of type {} is present", feature_name)),
         }
+        reveal_item(&mut self.items, id).ok_or_else(|| GameError::MissingItem(id.to_string()))
     }
+
+    pub fn remove_feature(&mut self, feature_id: &str) -> GameResult<String> {
+        match self.features.iter().position(|i| i.id == feature_id) {
+            Some(index) => Ok(self.features.remove(index).name),
+            None => Err(GameError::MissingItem(feature_id.to_string())),
+        }
+    }
+}
+
+/// Makes a hidden item among the items, or anywhere inside them, visible,
+/// returning its name
+fn reveal_item(items: &mut [Item], id: &str) -> Option<String> {
+    items.iter_mut().find_map(|item| {
+        if item.id == id {
+            item.properties.hidden = false;
+            return Some(item.name.clone());
+        }
+        item.container
+            .as_mut()
+            .and_then(|container| reveal_item(&mut container.contents, id))
+    })
 }
@@ -0,0 +1,107 @@
+#![warn(missing_docs)]
+
+//! The `errors` module describes what can go wrong while playing, and
+//! separates the refusals the player sees as part of the game from the
+//! conditions that end it.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::parser::ParseError;
+
+/// The ways an operation on the world can fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameError {
+    /// There is no exit in that direction
+    UnknownDirection(String),
+    /// There is no item or feature of that name here
+    MissingItem(String),
+    /// The player is not carrying an item of that name
+    NotCarrying(String),
+    /// A room the game needs does not exist, so the world is broken
+    MissingRoom(String),
+    /// The player's input could not be understood
+    NotUnderstood(ParseError),
+    /// The action is understood but not possible, with the reason to show
+    /// the player
+    Refused(String),
+    /// The world definition is malformed or fails validation
+    InvalidWorld(String),
+    /// A file could not be read or written
+    Io(String),
+    /// Saved data could not be converted to or from JSON
+    Serialization(String),
+    /// The player has asked to stop playing
+    QuitRequested,
+}
+
+/// The results of operations on the world
+pub type GameResult<T> = Result<T, GameError>;
+
+impl GameError {
+    /// Returns true if the game cannot carry on after the error
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, GameError::MissingRoom(_))
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::UnknownDirection(direction) => {
+                write!(f, "{} is not a valid direction", direction)
+            }
+            GameError::MissingItem(name) => write!(f, "There is no {} here", name),
+            GameError::NotCarrying(name) => write!(f, "You are not carrying {}", name),
+            GameError::MissingRoom(room) => write!(f, "Room '{}' does not exist", room),
+            GameError::NotUnderstood(err) => write!(f, "{}", err),
+            GameError::Refused(reason)
+            | GameError::InvalidWorld(reason)
+            | GameError::Io(reason)
+            | GameError::Serialization(reason) => write!(f, "{}", reason),
+            GameError::QuitRequested => write!(f, "Exiting"),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+/// How a command turned out, for the game to decide whether to carry on
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The command was carried out or refused, and play continues
+    Continue(String),
+    /// The player asked to stop playing
+    Quit(String),
+    /// Something went wrong that the game cannot recover from
+    Failed(String),
+}
+
+impl Outcome {
+    /// The text to show the player
+    pub fn text(&self) -> &str {
+        match self {
+            Outcome::Continue(text) | Outcome::Quit(text) | Outcome::Failed(text) => text,
+        }
+    }
+
+    /// Returns true if no more commands should be given
+    pub fn ends_game(&self) -> bool {
+        !matches!(self, Outcome::Continue(_))
+    }
+}
+
+impl From<GameResult<String>> for Outcome {
+    fn from(result: GameResult<String>) -> Outcome {
+        match result {
+            Ok(text) => Outcome::Continue(text),
+            Err(GameError::QuitRequested) => Outcome::Quit(GameError::QuitRequested.to_string()),
+            Err(err) if err.is_fatal() => Outcome::Failed(err.to_string()),
+            Err(err) => Outcome::Continue(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./errors_tests.rs"]
+mod errors_tests;
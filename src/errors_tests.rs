@@ -0,0 +1,20 @@
+use super::{GameError, Outcome};
+
+#[test]
+fn test_refusals_continue_and_fatal_errors_end_the_game() {
+    let refused = Outcome::from(Err(GameError::Io("could not save game".to_string())));
+    let quit = Outcome::from(Err(GameError::QuitRequested));
+    let failed = Outcome::from(Err(GameError::MissingRoom("attic".to_string())));
+
+    assert_eq!(
+        refused,
+        Outcome::Continue("could not save game".to_string())
+    );
+    assert_eq!(quit, Outcome::Quit("Exiting".to_string()));
+    assert_eq!(
+        failed,
+        Outcome::Failed("Room 'attic' does not exist".to_string())
+    );
+    assert!(!refused.ends_game());
+    assert!(quit.ends_game() && failed.ends_game());
+}
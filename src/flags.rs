@@ -0,0 +1,151 @@
+#![warn(missing_docs)]
+
+//! The `flags` module holds the game-wide variables that remember what has
+//! happened in the world, and the conditions that test them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world_building::World;
+
+/// The value of a flag. Written in world files and saves as a plain JSON
+/// boolean, number or string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FlagValue {
+    /// Something has or has not happened
+    Bool(bool),
+    /// A count of something, such as visits to a room
+    Counter(i64),
+    /// A piece of text, such as the name of a choice the player made
+    Text(String),
+}
+
+impl FlagValue {
+    /// Returns true for `true`, non-zero counters and non-empty text
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            FlagValue::Bool(value) => *value,
+            FlagValue::Counter(value) => *value != 0,
+            FlagValue::Text(value) => !value.is_empty(),
+        }
+    }
+}
+
+/// The flags of a world, stored by name. Flags that were never set are
+/// false, zero or empty.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Flags(HashMap<String, FlagValue>);
+
+impl Flags {
+    /// Gets the value of a flag, if it has been set
+    pub fn get(&self, name: &str) -> Option<&FlagValue> {
+        self.0.get(name)
+    }
+
+    /// Sets a flag to a value, replacing any previous value
+    pub fn set(&mut self, name: &str, value: FlagValue) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    /// Removes a flag, returning it to its unset state
+    pub fn clear(&mut self, name: &str) {
+        self.0.remove(name);
+    }
+
+    /// Checks if a flag is set to a truthy value
+    pub fn is_set(&self, name: &str) -> bool {
+        self.get(name).is_some_and(FlagValue::is_truthy)
+    }
+
+    /// Gets a flag as a counter, treating unset and non-counter flags as zero
+    pub fn counter(&self, name: &str) -> i64 {
+        match self.get(name) {
+            Some(FlagValue::Counter(value)) => *value,
+            _ => 0,
+        }
+    }
+
+    /// Adds to a counter, starting from zero if it is not set
+    pub fn add(&mut self, name: &str, amount: i64) {
+        let value = self.counter(name) + amount;
+        self.set(name, FlagValue::Counter(value));
+    }
+}
+
+/// A test of the world's state, used to decide whether rules apply and
+/// which descriptions are shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// The flag is set to a truthy value
+    FlagSet(String),
+    /// The flag is unset or set to a falsy value
+    FlagUnset(String),
+    /// The flag has exactly this value
+    FlagEquals {
+        /// The name of the flag
+        flag: String,
+        /// The value it must have
+        value: FlagValue,
+    },
+    /// The counter is at least this value
+    CounterAtLeast {
+        /// The name of the counter
+        flag: String,
+        /// The smallest value that satisfies the condition
+        value: i64,
+    },
+    /// The player is carrying the item with this id
+    HasItem(String),
+}
+
+impl Condition {
+    /// Checks if the condition holds in the world
+    pub fn holds(&self, world: &World) -> bool {
+        match self {
+            Condition::FlagSet(flag) => world.flags.is_set(flag),
+            Condition::FlagUnset(flag) => !world.flags.is_set(flag),
+            Condition::FlagEquals { flag, value } => world.flags.get(flag) == Some(value),
+            Condition::CounterAtLeast { flag, value } => world.flags.counter(flag) >= *value,
+            Condition::HasItem(item_id) => world.player.has_item(item_id),
+        }
+    }
+}
+
+/// Checks if every condition holds, so an empty list always holds
+pub fn all_hold(conditions: &[Condition], world: &World) -> bool {
+    conditions.iter().all(|condition| condition.holds(world))
+}
+
+/// Text that replaces a default when its conditions hold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalText {
+    /// The conditions that must all hold
+    pub when: Vec<Condition>,
+    /// The text to use
+    pub text: String,
+}
+
+/// Picks the first conditional text whose conditions hold, or the default
+///
+/// # Arguments
+///
+/// * `default` - the text to use if no alternative applies
+/// * `alternatives` - the conditional texts, in order of preference
+/// * `world` - the world the conditions are tested against
+pub fn select_text<'a>(
+    default: &'a str,
+    alternatives: &'a [ConditionalText],
+    world: &World,
+) -> &'a str {
+    alternatives
+        .iter()
+        .find(|alternative| all_hold(&alternative.when, world))
+        .map_or(default, |alternative| &alternative.text)
+}
+
+#[cfg(test)]
+#[path = "./flags_tests.rs"]
+mod flags_tests;
@@ -0,0 +1,79 @@
+use super::{Condition, FlagValue, Flags};
+use crate::world_building::World;
+
+#[test]
+fn test_flags_round_trip_as_plain_json() {
+    // Arrange
+    let mut flags = Flags::default();
+    flags.set("torches lit", FlagValue::Bool(true));
+    flags.add("visits", 2);
+    flags.set("password", FlagValue::Text("swordfish".to_string()));
+
+    // Act
+    let json = serde_json::to_value(&flags).unwrap();
+    let result: Flags = serde_json::from_value(json.clone()).unwrap();
+
+    // Assert
+    assert_eq!(json["torches lit"], true);
+    assert_eq!(json["visits"], 2);
+    assert_eq!(json["password"], "swordfish");
+    assert_eq!(result, flags);
+}
+
+#[test]
+fn test_conditions_read_flags_and_inventory() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "lamp" }] },
+        "rooms": [{ "id": "hall", "description": "A hall" }],
+        "flags": { "visits": 3, "mood": "grumpy", "door open": false }
+    }"#;
+    let world = World::from_json(json).unwrap();
+    let condition = |json: &str| serde_json::from_str::<Condition>(json).unwrap();
+
+    // Act / Assert
+    assert!(condition(r#"{ "counter_at_least": { "flag": "visits", "value": 3 } }"#).holds(&world));
+    assert!(
+        !condition(r#"{ "counter_at_least": { "flag": "visits", "value": 4 } }"#).holds(&world)
+    );
+    assert!(condition(r#"{ "flag_equals": { "flag": "mood", "value": "grumpy" } }"#).holds(&world));
+    assert!(condition(r#"{ "flag_unset": "door open" }"#).holds(&world));
+    assert!(!condition(r#"{ "flag_set": "never set" }"#).holds(&world));
+    assert!(condition(r#"{ "has_item": "lamp" }"#).holds(&world));
+}
+
+#[test]
+fn test_rule_conditions_and_alternative_descriptions() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "match" }] },
+        "rooms": [{
+            "id": "hall",
+            "description": "A dark hall",
+            "alternative_descriptions": [
+                { "when": [{ "flag_set": "lit" }], "text": "A bright hall" }
+            ],
+            "features": [{ "name": "candle" }]
+        }],
+        "rules": [
+            { "item": "match", "feature": "candle", "conditions": [{ "flag_unset": "lit" }],
+              "message": "The candle burns", "effects": [{ "set_flag": "lit" }, { "increment": { "flag": "matches used" } }] }
+        ]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let first = world.use_item("use", "match", "candle");
+    let second = world.use_item("use", "match", "candle");
+
+    // Assert
+    assert_eq!(first.unwrap(), "The candle burns");
+    assert_eq!(second.unwrap_err().to_string(), "You cannot do that here");
+    assert_eq!(world.flags.counter("matches used"), 1);
+    let room = world.player_room().unwrap();
+    assert!(room
+        .get_full_description(&world)
+        .starts_with("\nA bright hall"));
+}
@@ -0,0 +1,368 @@
+#![warn(missing_docs)]
+
+//! The `game` module runs the game one command at a time, independent of
+//! where the commands come from or where the output goes.
+
+use std::collections::VecDeque;
+
+use crate::errors::{GameError, GameResult, Outcome};
+use crate::history::History;
+use crate::parser::{self, Action};
+use crate::resolver::{self, Clarification, Referents};
+use crate::saves::{SaveSlots, DEFAULT_SLOT};
+use crate::world_building::World;
+use crate::world_state::WorldState;
+
+/// The result of one step of the game
+#[derive(Debug, Default, PartialEq)]
+pub struct Response {
+    /// The text to show the player
+    pub text: String,
+    /// True if the player has moved to another room, in which case `text`
+    /// ends with the description of the new room
+    pub room_changed: bool,
+    /// True if the game has ended and no more input should be given
+    pub game_over: bool,
+}
+
+/// A game being played in a world
+#[derive(Debug)]
+pub struct Game {
+    /// The current state of the world
+    pub world: World,
+    /// The world as it was at the start of the game, which saves record
+    /// their changes against
+    definition: World,
+    /// Where the game is saved to and loaded from
+    pub saves: SaveSlots,
+    /// The earlier states of the world, for undo and redo
+    pub history: History,
+    /// A question put to the player by the previous command, which the next
+    /// input may be answering
+    pending: Option<Box<Clarification>>,
+    /// The commands waiting for the player to answer the pending question
+    queued: VecDeque<Action>,
+    /// The objects "it" and "them" stand for
+    referents: Referents,
+    /// The action performed last, which "again" repeats
+    last_action: Option<Action>,
+}
+
+impl Game {
+    /// Start a game in a world, saving to the default save directory
+    pub fn new(world: World) -> Game {
+        Game {
+            definition: world.clone(),
+            world,
+            saves: SaveSlots::default(),
+            history: History::default(),
+            pending: None,
+            queued: VecDeque::new(),
+            referents: Referents::default(),
+            last_action: None,
+        }
+    }
+
+    /// Start a game in the world defined in a file
+    ///
+    /// # Errors
+    /// The world could not be loaded
+    pub fn from_file(path: &str) -> GameResult<Game> {
+        World::from_file(path).map(Game::new)
+    }
+
+    /// Greets the player and describes where they start
+    pub fn begin(&self) -> Response {
+        match self.world.describe_player_room() {
+            Ok(description) => Response {
+                text: format!("Welcome\n{}", description),
+                ..Response::default()
+            },
+            Err(err) => Response {
+                text: err.to_string(),
+                game_over: true,
+                ..Response::default()
+            },
+        }
+    }
+
+    /// Perform the commands in one line typed by the player
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - the player's commands
+    pub fn step(&mut self, input: &str) -> Response {
+        let location = self.world.player_location.clone();
+
+        let outcome = self.perform_commands(input);
+        Response {
+            text: outcome.text().to_string(),
+            room_changed: !outcome.ends_game() && self.world.player_location != location,
+            game_over: outcome.ends_game(),
+        }
+    }
+
+    /// Parse the user input and perform its commands in order, describing
+    /// each room the player arrives in. Stops at the first command that
+    /// fails, or that asks the player a question, in which case the rest
+    /// are carried out once it is answered.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_input` - the user's commands
+    fn perform_commands(&mut self, user_input: &str) -> Outcome {
+        let answer = self
+            .pending
+            .take()
+            .and_then(|clarification| clarification.answer(user_input));
+        let queue = match answer {
+            Some(action) => {
+                let mut queue = std::mem::take(&mut self.queued);
+                queue.push_front(action);
+                queue
+            }
+            None => parser::parse_commands(user_input, &self.world.vocabulary).into(),
+        };
+        self.queued.clear();
+
+        let before = self.world.clone();
+        let mut recordable = false;
+        let outcome = self.perform_queue(queue, &mut recordable);
+        if recordable && self.changed_since(&before) {
+            self.history.record(&before);
+        }
+        outcome
+    }
+
+    /// Perform queued commands until they run out, one fails or one asks
+    /// the player a question
+    ///
+    /// # Arguments
+    ///
+    /// * `queue` - the commands to perform, in order
+    /// * `recordable` - set when a command is performed whose changes undo
+    ///   should take back
+    fn perform_queue(&mut self, mut queue: VecDeque<Action>, recordable: &mut bool) -> Outcome {
+        let mut replies = Vec::new();
+        while let Some(action) = queue.pop_front() {
+            let location = self.world.player_location.clone();
+            let result = match self.expand(&action) {
+                Some(Ok(actions)) => {
+                    actions
+                        .into_iter()
+                        .rev()
+                        .for_each(|action| queue.push_front(action));
+                    continue;
+                }
+                Some(Err(err)) => Err(err),
+                None => self.perform_action(action, recordable),
+            };
+
+            let result = match result {
+                Ok(text) if self.world.player_location != location => {
+                    replies.push(text);
+                    self.world.describe_player_room()
+                }
+                result => result,
+            };
+
+            match result {
+                Ok(text) => {
+                    replies.push(text);
+                    if self.pending.is_some() {
+                        self.queued = queue;
+                        break;
+                    }
+                }
+                Err(err) => {
+                    let failure = Outcome::from(Err(err));
+                    replies.push(failure.text().to_string());
+                    if !failure.ends_game() && !queue.is_empty() {
+                        replies.push(skipped(queue.len()));
+                    }
+                    let text = replies.join("\n");
+                    return match failure {
+                        Outcome::Continue(_) => Outcome::Continue(text),
+                        Outcome::Quit(_) => Outcome::Quit(text),
+                        Outcome::Failed(_) => Outcome::Failed(text),
+                    };
+                }
+            }
+        }
+
+        Outcome::Continue(replies.join("\n"))
+    }
+
+    /// Returns true if the world differs from an earlier one in more than
+    /// the number of turns taken
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - the world as it was earlier
+    fn changed_since(&self, before: &World) -> bool {
+        let mut earlier = WorldState::capture(before, &self.definition);
+        earlier.turns = self.world.turns;
+        earlier != WorldState::capture(&self.world, &self.definition)
+    }
+
+    /// Turns an action that stands for others into them: "again" into the
+    /// action performed last, pronouns into the objects they stand for, and
+    /// "all" into each item it covers. Returns `None` for any other action.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - the action parsed from the user's input
+    fn expand(&self, action: &Action) -> Option<GameResult<Vec<Action>>> {
+        match action {
+            Action::Again => self.last_action.clone().map(|action| Ok(vec![action])),
+            _ => self
+                .referents
+                .substitute(action)
+                .or_else(|| expand_all(&self.world, action)),
+        }
+    }
+
+    /// Resolve the objects an action refers to and perform it if possible
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - the action as parsed from the user's input
+    /// * `recordable` - set when the action should be recorded in the
+    ///   history, so that undo takes back the whole line, and cleared when
+    ///   the world is replaced by undo or redo
+    ///
+    /// # Errors
+    ///
+    /// The command was refused, the player asked to quit, or the world is
+    /// broken
+    fn perform_action(&mut self, action: Action, recordable: &mut bool) -> GameResult<String> {
+        let world = &mut self.world;
+        let action = match resolver::resolve_action(world, action) {
+            Ok(action) => action,
+            Err(clarification) => {
+                let question = clarification.question();
+                self.pending = Some(clarification);
+                return Ok(question);
+            }
+        };
+
+        if let Some(err) = resolver::unknown_word(world, &action) {
+            return Err(GameError::NotUnderstood(err));
+        }
+        self.referents.remember(&action);
+        if !matches!(action, Action::Unknown(_) | Action::Again) {
+            self.last_action = Some(action.clone());
+        }
+
+        if action.takes_turn() || matches!(action, Action::Load(_)) {
+            *recordable = true;
+        }
+        if action.takes_turn() {
+            world.turns += 1;
+        }
+
+        match action {
+            Action::Exit => Err(GameError::QuitRequested),
+            Action::Undo => match self.history.undo(world) {
+                Some(previous) => {
+                    *world = previous;
+                    *recordable = false;
+                    self.pending = None;
+                    self.queued.clear();
+                    Ok("You take back your last move".to_string())
+                }
+                None => Ok("There is nothing to undo".to_string()),
+            },
+            Action::Redo => match self.history.redo(world) {
+                Some(next) => {
+                    *world = next;
+                    *recordable = false;
+                    self.pending = None;
+                    self.queued.clear();
+                    Ok("You make your move again".to_string())
+                }
+                None => Ok("There is nothing to redo".to_string()),
+            },
+            Action::Again => Err(GameError::Refused("There is nothing to repeat".to_string())),
+            Action::Save(name) => self.saves.save(slot_name(&name), world, &self.definition),
+            Action::Load(name) => {
+                *world = self.saves.load(slot_name(&name), &self.definition)?;
+                Ok("game loaded".to_string())
+            }
+            Action::Saves => self.saves.describe(),
+            Action::Inventory => Ok(world.player.list_inventory()),
+            Action::Look => world.describe_player_room(),
+            Action::Examine(name) => world.examine(&name),
+            Action::Move(direction) => world.move_player(&direction),
+            Action::Take(item_name) => world.take_item(&item_name),
+            Action::TakeFrom(item_name, container) => world.take_from(&item_name, &container),
+            Action::Drop(item_name) => world.drop_item(&item_name),
+            Action::Put(item_name, target) => world.put_item(&item_name, &target),
+            Action::Open(id) => world.open(&id),
+            Action::Close(id) => world.close(&id),
+            Action::LookIn(id) => world.look_in(&id),
+            Action::Use { verb, item, target } => world.use_item(&verb, &item, &target),
+            Action::Unknown(err) => Err(GameError::NotUnderstood(
+                resolver::object_without_verb(world, &err).unwrap_or(err),
+            )),
+        }
+    }
+}
+
+/// Expands "take all" and "drop all" into an action for each item they
+/// cover, or returns `None` for any other action
+fn expand_all(world: &World, action: &Action) -> Option<GameResult<Vec<Action>>> {
+    let (items, refusal, verb): (Vec<String>, &str, fn(String) -> Action) = match action {
+        Action::Take(phrase) if is_all(phrase) => (
+            world
+                .player_room()
+                .map(|room| {
+                    room.items
+                        .iter()
+                        .filter(|item| item.properties.is_listed() && item.properties.is_portable())
+                        .map(|item| item.id.clone())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            "There is nothing here to take",
+            Action::Take,
+        ),
+        Action::Drop(phrase) if is_all(phrase) => (
+            world
+                .player
+                .inventory
+                .iter()
+                .map(|item| item.id.clone())
+                .collect(),
+            "You are not carrying anything",
+            Action::Drop,
+        ),
+        _ => return None,
+    };
+
+    if items.is_empty() {
+        Some(Err(GameError::Refused(refusal.to_string())))
+    } else {
+        Some(Ok(items.into_iter().map(verb).collect()))
+    }
+}
+
+fn is_all(phrase: &str) -> bool {
+    phrase.eq_ignore_ascii_case("all") || phrase.eq_ignore_ascii_case("everything")
+}
+
+/// Tells the player how many of their commands were not carried out
+fn skipped(count: usize) -> String {
+    match count {
+        1 => "The remaining command was not carried out".to_string(),
+        _ => format!("The remaining {} commands were not carried out", count),
+    }
+}
+
+fn slot_name(name: &Option<String>) -> &str {
+    name.as_deref().unwrap_or(DEFAULT_SLOT)
+}
+
+#[cfg(test)]
+#[path = "./game_tests.rs"]
+mod game_tests;
@@ -0,0 +1,355 @@
+use super::{Game, Response};
+use crate::saves::SaveSlots;
+use crate::world_building::World;
+use crate::DEFAULT_WORLD;
+
+#[test]
+fn test_step_reports_room_changes() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+
+    // Act
+    let moved = game.step("go north");
+    let stayed = game.step("go north");
+
+    // Assert
+    assert!(moved.room_changed);
+    assert!(moved
+        .text
+        .starts_with("You have moved north\n\nA long corridor"));
+    assert_eq!(
+        stayed,
+        Response {
+            text: "The north door is locked".to_string(),
+            room_changed: false,
+            game_over: false,
+        }
+    );
+}
+
+#[test]
+fn test_full_playthrough_of_shipped_world() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    let commands = [
+        "go north",
+        "go west",
+        "take key",
+        "take tinderbox",
+        "go east",
+        "use key on north door",
+        "go north",
+        "use tinderbox on torches",
+        "go north",
+        "take loot",
+    ];
+
+    // Act
+    let responses: Vec<Response> = commands.iter().map(|c| game.step(c)).collect();
+    let quit = game.step("quit");
+
+    // Assert
+    assert!(responses.iter().all(|r| !r.game_over));
+    assert_eq!(game.world.player_location, "treasure room");
+    assert!(game.world.player.has_item("phat loot"));
+    assert!(quit.game_over);
+}
+
+#[test]
+fn test_undo_and_redo_moves() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    game.step("go north");
+    game.step("go west");
+    game.step("take key");
+
+    // Act
+    let undone = game.step("undo");
+    let undone_again = game.step("undo");
+    let redone = game.step("redo");
+
+    // Assert
+    assert_eq!(undone.text, "You take back your last move");
+    assert!(!game.world.player.has_item("key"));
+    assert!(undone_again.room_changed);
+    assert!(undone_again.text.contains("A long corridor"));
+    assert!(redone.text.starts_with("You make your move again"));
+    assert_eq!(game.world.player_location, "storeroom");
+    assert_eq!(game.world.turns, 2);
+    assert_eq!(game.step("redo").text, "You make your move again");
+    assert_eq!(game.step("redo").text, "There is nothing to redo");
+}
+
+#[test]
+fn test_undo_skips_lines_that_change_nothing() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    game.step("go north");
+    game.step("take key");
+    game.step("look");
+
+    // Act
+    let undone = game.step("undo");
+    let nothing_left = game.step("undo");
+
+    // Assert
+    assert!(undone.room_changed);
+    assert_eq!(game.world.player_location, "entrance");
+    assert_eq!(nothing_left.text, "There is nothing to undo");
+}
+
+#[test]
+fn test_refusals_do_not_end_the_game() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    game.saves = SaveSlots::new("/dev/null/saves");
+
+    // Act
+    let used = game.step("use key on north door");
+    let saved = game.step("save");
+    let dotted = game.step("save my.game");
+    let quit = game.step("quit");
+
+    // Assert
+    assert_eq!(used.text, "You are not carrying key");
+    assert!(!used.game_over);
+    assert_eq!(saved.text, "could not save game");
+    assert!(!saved.game_over);
+    assert_eq!(
+        dotted.text,
+        "my.game is not a valid save name, use letters, numbers, - and _"
+    );
+    assert!(quit.game_over);
+}
+
+#[test]
+fn test_bare_directions_follow_the_worlds_exits() {
+    // Arrange
+    let json = r#"{
+        "start": "lab",
+        "rooms": [
+            { "id": "lab", "description": "A lab", "exits": { "Portal": "void" } },
+            { "id": "void", "description": "The void", "exits": { "back": "lab" } }
+        ]
+    }"#;
+    let mut game = Game::new(World::from_json(json).unwrap());
+
+    // Act
+    let through = game.step("portal");
+    let back = game.step("back");
+    let nowhere = game.step("north");
+
+    // Assert
+    assert!(through
+        .text
+        .starts_with("You have moved portal\n\nThe void"));
+    assert!(back.room_changed);
+    assert_eq!(nowhere.text, "north is not a valid direction");
+}
+
+#[test]
+fn test_command_chains_stop_at_the_first_failure() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    game.step("go north");
+
+    // Act
+    let taken = game.step("go west, take all then go east");
+    let dropped = game.step("drop key and tinderbox, take loot, go west, look");
+    let undone = game.step("undo");
+
+    // Assert
+    assert!(taken.text.contains("Picked up key\nPicked up tinderbox"));
+    assert!(taken
+        .text
+        .contains("You have moved east\n\nA long corridor"));
+    assert!(!taken.room_changed);
+    assert_eq!(
+        dropped.text,
+        "Dropped key\nDropped tinderbox\nThere is no loot here\n\
+         The remaining 2 commands were not carried out"
+    );
+    assert_eq!(game.world.player_location, "corridor");
+    assert_eq!(undone.text, "You take back your last move");
+    assert!(game.world.player.has_item("key"));
+    assert!(game.world.player.has_item("tinderbox"));
+    assert_eq!(game.step("take all").text, "There is nothing here to take");
+}
+
+#[test]
+fn test_pronouns_and_again() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+    let nothing = game.step("again");
+    game.step("go north. go west");
+
+    // Act
+    let taken = game.step("take key and tinderbox. drop them");
+    let again = game.step("take key. g");
+    let used = game.step("go east. use it on door");
+
+    // Assert
+    assert_eq!(nothing.text, "There is nothing to repeat");
+    assert_eq!(
+        taken.text,
+        "Picked up key\nPicked up tinderbox\nDropped key\nDropped tinderbox"
+    );
+    assert_eq!(again.text, "Picked up key\nThere is no key here");
+    assert!(used
+        .text
+        .ends_with("You unlock the north door with the key"));
+}
+
+#[test]
+fn test_unknown_words_are_reported_with_suggestions() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+
+    // Act
+    let verb = game.step("tkae key");
+    let object = game.step("examine dor");
+    let elsewhere = game.step("take crown");
+    let later = game.step("examine lit torches");
+
+    // Assert
+    assert_eq!(
+        verb.text,
+        "I don't know the word 'tkae'. Did you mean 'take'?"
+    );
+    assert_eq!(
+        object.text,
+        "I don't know the word 'dor'. Did you mean 'door'?"
+    );
+    assert_eq!(elsewhere.text, "There is no crown here");
+    assert!(!later.text.starts_with("I don't know"));
+    assert_eq!(game.world.turns, 2);
+}
+
+#[test]
+fn test_rules_add_verbs() {
+    // Arrange
+    let json = r#"{
+        "start": "gate",
+        "player": { "name": "Bob", "inventory": [{ "name": "coin" }] },
+        "rooms": [{ "id": "gate", "description": "A gate", "features": [{ "name": "guard" }] }],
+        "rules": [{ "verbs": ["give"], "item": "coin", "feature": "guard", "message": "The guard nods" }]
+    }"#;
+    let mut game = Game::new(World::from_json(json).unwrap());
+
+    // Act
+    let given = game.step("give the coin to the guard");
+    let used = game.step("use coin on guard");
+    let unasked = game.step("give coin");
+
+    // Assert
+    assert_eq!(given.text, "The guard nods");
+    assert_eq!(used.text, "You cannot do that here");
+    assert_eq!(unasked.text, "Give the coin how?");
+}
+
+#[test]
+fn test_take_all_leaves_fixed_and_scenery_behind() {
+    // Arrange
+    let json = r#"{
+        "start": "garden",
+        "player": { "name": "Bob" },
+        "rooms": [{
+            "id": "garden",
+            "description": "A garden",
+            "features": [{ "name": "statue", "refusal": "The statue is far too heavy" }],
+            "items": [{ "name": "rake" }, { "name": "bench", "scenery": true }, { "name": "sundial", "fixed": true }]
+        }]
+    }"#;
+    let mut game = Game::new(World::from_json(json).unwrap());
+
+    // Act
+    let statue = game.step("take the statue");
+    let all = game.step("take all");
+
+    // Assert
+    assert_eq!(statue.text, "The statue is far too heavy");
+    assert_eq!(all.text, "Picked up rake");
+    assert_eq!(game.world.player.inventory.len(), 1);
+}
+
+#[test]
+fn test_again_with_nothing_to_repeat_does_not_hang() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+
+    // Act
+    let first = game.step("again");
+    let second = game.step("again");
+    let short = game.step("g");
+
+    // Assert
+    assert_eq!(first.text, "There is nothing to repeat");
+    assert_eq!(second.text, "There is nothing to repeat");
+    assert_eq!(short.text, "There is nothing to repeat");
+}
+
+#[test]
+fn test_hidden_objects_are_unknown_until_revealed() {
+    // Arrange
+    let json = r#"{
+        "start": "study",
+        "player": { "name": "Bob", "inventory": [{ "name": "lever" }] },
+        "rooms": [{
+            "id": "study",
+            "description": "A study",
+            "features": [{ "name": "bookcase" }],
+            "items": [{ "name": "safe", "hidden": true, "fixed": true }]
+        }],
+        "rules": [{ "item": "lever", "feature": "bookcase", "message": "The bookcase swings aside",
+                    "effects": [{ "reveal": "safe" }] }]
+    }"#;
+    let mut game = Game::new(World::from_json(json).unwrap());
+
+    // Act
+    let hidden = game.step("examine safe");
+    game.step("use lever on bookcase");
+    let revealed = game.step("take safe");
+
+    // Assert
+    assert_eq!(hidden.text, "I don't know the word 'safe'");
+    assert_eq!(revealed.text, "You cannot take the safe");
+}
+
+#[test]
+fn test_known_objects_without_a_verb_are_asked_about() {
+    // Arrange
+    let mut game = Game::from_file(DEFAULT_WORLD).unwrap();
+
+    // Act
+    let bag = game.step("bag");
+    let rusty = game.step("rusty");
+    let unknown = game.step("dance");
+
+    // Assert
+    assert_eq!(bag.text, "What do you want to do with the bag?");
+    assert_eq!(rusty.text, "What do you want to do with the key?");
+    assert_eq!(unknown.text, "I don't know the word 'dance'");
+}
+
+#[test]
+fn test_go_in_follows_an_in_exit() {
+    // Arrange
+    let json = r#"{
+        "start": "yard",
+        "rooms": [
+            { "id": "yard", "description": "A yard", "exits": { "in": "barn" } },
+            { "id": "barn", "description": "A barn", "exits": { "out": "yard" } }
+        ]
+    }"#;
+    let mut game = Game::new(World::from_json(json).unwrap());
+
+    // Act
+    let went_in = game.step("go in");
+    game.step("go out");
+    let went_into = game.step("go into");
+
+    // Assert
+    assert!(went_in.text.starts_with("You have moved in\n\nA barn"));
+    assert!(went_into.room_changed);
+    assert_eq!(game.world.player_location, "barn");
+}
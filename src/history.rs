@@ -0,0 +1,83 @@
+#![warn(missing_docs)]
+
+//! The `history` module keeps snapshots of the world from before each
+//! command, so that commands can be undone and redone.
+
+use std::collections::VecDeque;
+
+use crate::world_building::World;
+
+/// The number of commands that can be undone when no limit is configured
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Snapshots of the world to undo back to and redo forward to
+#[derive(Debug, Clone)]
+pub struct History {
+    /// The world before each command, oldest first
+    past: VecDeque<World>,
+    /// The world after each undone command, most recently undone last
+    future: Vec<World>,
+    /// The most snapshots kept in `past`
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl History {
+    /// Keep up to `limit` snapshots, forgetting the oldest beyond that
+    pub fn new(limit: usize) -> History {
+        History {
+            past: VecDeque::new(),
+            future: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Record the world before a command changes it. Anything undone can
+    /// no longer be redone.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world as it is before the command
+    pub fn record(&mut self, world: &World) {
+        self.future.clear();
+        if self.limit == 0 {
+            return;
+        }
+        if self.past.len() == self.limit {
+            self.past.pop_front();
+        }
+        self.past.push_back(world.clone());
+    }
+
+    /// Step back to the world before the last command, if there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - the world as it is now, kept so it can be redone
+    pub fn undo(&mut self, current: &World) -> Option<World> {
+        let previous = self.past.pop_back()?;
+        self.future.push(current.clone());
+        Some(previous)
+    }
+
+    /// Step forward to the world after the last undone command, if there is
+    /// one
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - the world as it is now, kept so it can be undone again
+    pub fn redo(&mut self, current: &World) -> Option<World> {
+        let next = self.future.pop()?;
+        self.past.push_back(current.clone());
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+#[path = "./history_tests.rs"]
+mod history_tests;
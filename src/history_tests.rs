@@ -0,0 +1,49 @@
+use super::History;
+use crate::world_building::World;
+
+fn at(location: &str) -> World {
+    World {
+        player_location: location.to_string(),
+        ..World::default()
+    }
+}
+
+#[test]
+fn test_undo_and_redo() {
+    // Arrange
+    let mut history = History::default();
+    history.record(&at("entrance"));
+    history.record(&at("corridor"));
+
+    // Act
+    let undone = history.undo(&at("storeroom")).unwrap();
+    let redone = history.redo(&undone).unwrap();
+    let nothing_to_redo = history.redo(&redone);
+
+    // Assert
+    assert_eq!(undone.player_location, "corridor");
+    assert_eq!(redone.player_location, "storeroom");
+    assert!(nothing_to_redo.is_none());
+}
+
+#[test]
+fn test_history_is_bounded_and_recording_clears_redo() {
+    // Arrange
+    let mut history = History::new(2);
+    for location in &["entrance", "corridor", "storeroom"] {
+        history.record(&at(location));
+    }
+
+    // Act
+    let first = history.undo(&at("now")).unwrap();
+    let second = history.undo(&first).unwrap();
+    let third = history.undo(&second);
+    history.record(&at("elsewhere"));
+    let redo = history.redo(&at("now"));
+
+    // Assert
+    assert_eq!(first.player_location, "storeroom");
+    assert_eq!(second.player_location, "corridor");
+    assert!(third.is_none());
+    assert!(redo.is_none());
+}
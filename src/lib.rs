@@ -3,129 +3,66 @@ extern crate derive_new;
 #[macro_use]
 extern crate log;
 
-#[macro_use]
-pub mod world_building;
 pub mod entities;
+pub mod errors;
+pub mod flags;
+pub mod game;
+pub mod history;
 pub mod parser;
+pub mod playthrough;
+pub mod resolver;
+pub mod save_migrations;
+pub mod saves;
+pub mod vocabulary;
+pub mod world_building;
+pub mod world_state;
+pub mod world_validation;
 
-use entities::Player;
-use parser::*;
+use game::Game;
+use saves::SaveSlots;
 use std::io::{self};
-use world_building::*;
 
-/// Create the world and start the main loop
-pub fn start() {
-    let mut world = let_there_be_light();
+/// The world definition played when none is given on the command line
+pub const DEFAULT_WORLD: &str = "worlds/dungeon.json";
 
-    println!("Welcome");
+/// Load the world and play it in the terminal
+///
+/// # Arguments
+///
+/// * `world_path` - the path of the world definition file to play
+/// * `save_dir` - the directory games are saved in
+pub fn start(world_path: &str, save_dir: &str) {
+    let mut game = match Game::from_file(world_path) {
+        Ok(mut game) => {
+            game.saves = SaveSlots::new(save_dir);
+            game
+        }
+        Err(err) => {
+            println!("Could not load world\n{}", err);
+            return;
+        }
+    };
 
+    let mut response = game.begin();
     loop {
         // Write output
-        match world.get_player_room() {
-            None => {
-                println!("Location does not exist");
-                break;
-            }
-            Some(room) => println!("{}", room.get_full_description()),
+        println!("{}", response.text);
+        if response.game_over {
+            break;
         }
 
         // Get input
         let mut user_input = String::new();
         match io::stdin().read_line(&mut user_input) {
+            Ok(0) => break,
+            Ok(_) => {}
             Err(error) => {
                 println!("error: {}", error);
                 break;
             }
-            _ => {}
         }
 
         // Perform actions
-        match perform_action(&mut world, &user_input) {
-            Err(err) => {
-                println!("{}", err);
-                break;
-            }
-            Ok(output) => println!("{}", output),
-        }
-    }
-}
-
-/// Create the initial world state
-fn let_there_be_light() -> World {
-    let mut world = shaper_of_worlds!(
-        location = "entrance",
-        rooms = [
-            [
-                "entrance",
-                "The dungeon entrance",
-                items = [],
-                features = [],
-                exits = ["north" => "corridor"]
-            ]
-            [
-                "corridor",
-                "A long corridor",
-                items = [],
-                features = ["north door"],
-                exits = ["south" => "entrance" "west" => "storeroom"]
-            ]
-            [
-                "storeroom",
-                "An old dusty storeroom",
-                items = ["key", "tinderbox"],
-                features = [],
-                exits = ["east" => "corridor"]
-            ]
-            [
-                "dark room",
-                "A dimly lit room with unlit torches on the walls",
-                items = [],
-                features = [],
-                exits = ["south" => "corridor"]
-            ]
-            [
-                "treasure room",
-                "A room full of shiney things",
-                items = ["phat loot"],
-                features = [],
-                exits = ["south" => "dark room"]
-            ]
-        ]
-    );
-
-    let player = Player::new("Bob".to_string());
-    world.player = player;
-    return world;
-}
-
-/// Parse the user input and perform the action if possible
-///
-/// # Arguments
-///
-/// * `world` - the current world state
-/// * `user_input` - the user command
-///
-/// # Errors
-///
-/// The user command is not valid
-fn perform_action(world: &mut World, user_input: &str) -> Result<String, String> {
-    let action = parser::parse_input(user_input);
-    match action {
-        parser::Action::Exit => Err("Exiting".to_string()),
-        Action::Save => world.save_state(),
-        Action::Load => world.load_state(),
-        Action::Inventory => Ok(world.player.list_inventory()),
-        Action::Move(direction) => acceptable_error(world.move_player(&direction)),
-        Action::Take(item_name) => acceptable_error(world.take_item(&*item_name)),
-        Action::Use(subject, target) => world.use_item(&subject, &target),
-        Action::Unknown => Ok("You cannot do that".to_string()),
-        _ => Ok("You cannot do that".to_string()),
-    }
-}
-
-fn acceptable_error(error: Result<String, String>) -> Result<String, String> {
-    match error {
-        Ok(msg) => Ok(msg),
-        Err(msg) => Ok(msg),
+        response = game.step(&user_input);
     }
 }
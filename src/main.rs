@@ -1,12 +1,19 @@
 extern crate simplelog;
 
 use simplelog::*;
+use std::env;
 use std::fs::File;
-use text_adventure::start;
+use text_adventure::saves::DEFAULT_SAVE_DIR;
+use text_adventure::{start, DEFAULT_WORLD};
 
 fn main() {
     CombinedLogger::init(vec![
-        TermLogger::new(LevelFilter::Warn, Config::default(), TerminalMode::Mixed).unwrap(),
+        TermLogger::new(
+            LevelFilter::Warn,
+            Config::default(),
+            TerminalMode::Mixed,
+            ColorChoice::Auto,
+        ),
         WriteLogger::new(
             LevelFilter::Info,
             Config::default(),
@@ -14,5 +21,22 @@ fn main() {
         ),
     ])
     .unwrap();
-    start();
+
+    // Usage: text_adventure [--saves <dir>] [world.json]
+    let mut world_path = DEFAULT_WORLD.to_string();
+    let mut save_dir = DEFAULT_SAVE_DIR.to_string();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--saves" => match args.next() {
+                Some(dir) => save_dir = dir,
+                None => {
+                    println!("--saves needs a directory");
+                    return;
+                }
+            },
+            _ => world_path = arg,
+        }
+    }
+    start(&world_path, &save_dir);
 }
@@ -1,57 +1,426 @@
-use nom::branch::*;
+use itertools::Itertools;
 use nom::bytes::complete::*;
 use nom::character::complete::*;
+use nom::combinator::*;
 use nom::error::ErrorKind;
+use nom::multi::*;
 use nom::sequence::*;
+use nom::IResult;
+use std::fmt;
+
+use crate::vocabulary::Vocabulary;
 
 type ParserPairResult<'a> = Result<(&'a str, (&'a str, &'a str)), nom::Err<(&'a str, ErrorKind)>>;
-type ParserTwoPairResult<'a> =
-    Result<(&'a str, ((&'a str, &'a str), (&'a str, &'a str))), nom::Err<(&'a str, ErrorKind)>>;
+type CommandResult<'a> = Result<
+    (
+        &'a str,
+        (
+            &'a str,
+            Option<&'a str>,
+            Option<(&'a str, Option<&'a str>)>,
+            &'a str,
+        ),
+    ),
+    nom::Err<(&'a str, ErrorKind)>,
+>;
+
+/// The words that introduce the indirect object of a command
+const PREPOSITIONS: [&str; 9] = [
+    "with", "on", "onto", "in", "into", "inside", "to", "from", "at",
+];
+
+/// Words that may come before an object without being part of its name
+pub const ARTICLES: [&str; 4] = ["a", "an", "some", "the"];
+
+/// The verbs that manage the game rather than act on the world, which the
+/// command grammar does not apply to
+const GAME_VERBS: [&str; 8] = [
+    "again",
+    "inventory",
+    "load",
+    "quit",
+    "redo",
+    "save",
+    "saves",
+    "undo",
+];
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum Action {
     Exit,
-    Load,
-    Save,
+    Undo,
+    Redo,
+    Again,
+    Load(Option<String>),
+    Save(Option<String>),
+    Saves,
     Inventory,
+    Look,
+    Examine(String),
     Move(String),
     Take(String),
-    Put(String),
-    Use(String, String),
-    Unknown,
+    TakeFrom(String, String),
+    Drop(String),
+    Put(String, String),
+    Open(String),
+    Close(String),
+    LookIn(String),
+    Use {
+        verb: String,
+        item: String,
+        target: String,
+    },
+    Unknown(ParseError),
+}
+
+/// A command broken down by the grammar `verb [direct] [preposition
+/// indirect]`, e.g. "unlock" "door" "with" "key". Verbs without a command of
+/// their own use an item on a target, so worlds can add verbs through their
+/// rules without any change to the parser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    /// The verb, e.g. "unlock"
+    pub verb: String,
+    /// The object the verb acts on, e.g. "door"
+    pub direct: Option<String>,
+    /// The word before the second object, e.g. "with"
+    pub preposition: Option<String>,
+    /// The second object, e.g. "key"
+    pub indirect: Option<String>,
+}
+
+impl From<Command> for Action {
+    /// Turns a command into the action it asks for, or the reason it is
+    /// incomplete. "With" marks the item being used, as in "light torch with
+    /// tinderbox", and any other preposition the target, as in "give coin to
+    /// guard".
+    fn from(command: Command) -> Action {
+        let Command {
+            verb,
+            direct,
+            preposition,
+            indirect,
+        } = command;
+
+        match (verb.as_str(), direct, preposition.as_deref(), indirect) {
+            ("look", None, None, None) => Action::Look,
+            ("look", None, Some("in"), Some(container)) => Action::LookIn(container),
+            ("examine", Some(object), None, None) => Action::Examine(object),
+            ("go", Some(direction), None, None) => Action::Move(direction),
+            ("go", None, Some("in"), None) => Action::Move("in".to_string()),
+            ("take", Some(object), None, None) => Action::Take(object),
+            ("take", Some(object), Some("from" | "in"), Some(container)) => {
+                Action::TakeFrom(object, container)
+            }
+            ("open", Some(object), None, None) => Action::Open(object),
+            ("close", Some(object), None, None) => Action::Close(object),
+            ("drop", Some(object), None, None) | ("put", Some(object), None, None) => {
+                Action::Drop(object)
+            }
+            ("put", Some(object), Some("in") | Some("on"), Some(place)) => {
+                Action::Put(object, place)
+            }
+            ("put", Some(object), Some(preposition @ ("in" | "on")), None)
+            | ("take", Some(object), Some(preposition @ ("from" | "in")), None) => {
+                Action::Unknown(ParseError::MissingTarget {
+                    object,
+                    preposition: Some(preposition.to_string()),
+                    verb,
+                })
+            }
+            (_, None, None, None) if verb != "look" => {
+                Action::Unknown(ParseError::MissingObject { verb })
+            }
+            ("look" | "examine" | "go" | "take" | "drop" | "put" | "open" | "close", ..) => {
+                Action::Unknown(ParseError::Misused { verb })
+            }
+            ("use", Some(item), Some(_), Some(target)) => Action::Use { verb, item, target },
+            (_, Some(target), Some("with"), Some(item)) => Action::Use { verb, item, target },
+            (_, Some(item), Some(_), Some(target)) => Action::Use { verb, item, target },
+            (_, Some(object), preposition, None) => Action::Unknown(ParseError::MissingTarget {
+                object,
+                preposition: preposition
+                    .map(str::to_string)
+                    .or_else(|| Some("on".to_string()).filter(|_| verb == "use")),
+                verb,
+            }),
+            _ => Action::Unknown(ParseError::Misused { verb }),
+        }
+    }
+}
+
+impl Action {
+    /// Returns true if the action advances the game, rather than managing it
+    pub fn takes_turn(&self) -> bool {
+        !matches!(
+            self,
+            Action::Exit
+                | Action::Undo
+                | Action::Redo
+                | Action::Again
+                | Action::Load(_)
+                | Action::Save(_)
+                | Action::Saves
+                | Action::Unknown(_)
+        )
+    }
+}
+
+/// Why the player's input could not be understood
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Nothing was typed
+    Empty,
+    /// A verb that needs an object was given none, e.g. "take"
+    MissingObject {
+        /// The verb, e.g. "take"
+        verb: String,
+    },
+    /// A verb that needs a second object was given only one, e.g. "use key"
+    MissingTarget {
+        /// The verb, e.g. "use"
+        verb: String,
+        /// The object that was given, e.g. "key"
+        object: String,
+        /// The word the second object follows, e.g. "on", if known
+        preposition: Option<String>,
+    },
+    /// A known verb followed by words it does not take, e.g. "look key"
+    Misused {
+        /// The verb, e.g. "look"
+        verb: String,
+    },
+    /// The name of something in the world given without a verb, e.g. "bag"
+    NoVerb {
+        /// The name of the thing, e.g. "bag"
+        object: String,
+    },
+    /// A word that is neither a verb, a direction nor the name of anything
+    UnknownWord {
+        /// The word, e.g. "lantren"
+        word: String,
+        /// A known word the player may have meant, e.g. "lantern"
+        suggestion: Option<String>,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "I beg your pardon?"),
+            ParseError::MissingObject { verb } if verb == "go" => write!(f, "Go where?"),
+            ParseError::MissingObject { verb } => write!(f, "{} what?", capitalize(verb)),
+            ParseError::MissingTarget {
+                verb,
+                object,
+                preposition: Some(preposition),
+            } => write!(
+                f,
+                "{} the {} {} what?",
+                capitalize(verb),
+                object,
+                preposition
+            ),
+            ParseError::MissingTarget {
+                verb,
+                object,
+                preposition: None,
+            } => write!(f, "{} the {} how?", capitalize(verb), object),
+            ParseError::Misused { verb } => {
+                write!(f, "I understood '{}' but not the rest of that", verb)
+            }
+            ParseError::NoVerb { object } => {
+                write!(f, "What do you want to do with the {}?", object)
+            }
+            ParseError::UnknownWord { word, suggestion } => {
+                write!(f, "I don't know the word '{}'", word)?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ". Did you mean '{}'?", suggestion),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut letters = word.chars();
+    match letters.next() {
+        Some(first) => first.to_uppercase().chain(letters).collect(),
+        None => String::new(),
+    }
 }
 
 /// Apply parsers to user's input until there is a match or all known
-/// commands have been tried.
+/// commands have been tried. The input's verb and any direction are first
+/// rewritten using the vocabulary, so the parsers only need to recognize one
+/// word for each. Input that is not a command but is a known direction,
+/// such as "n" or "portal", moves the player. Input that is neither is
+/// `Unknown`, with the reason it was not understood.
+///
+/// Commands that act on the world all share one grammar, see `Command`.
 ///
 /// # Arguments
 ///
 /// * `user_input` - the user input to parse for recognized actions
-pub fn parse_input(user_input: &str) -> Action {
-    let trimmed = user_input.trim();
+/// * `vocabulary` - the words the player can use for verbs and directions
+pub fn parse_input(user_input: &str, vocabulary: &Vocabulary) -> Action {
+    let normalized = vocabulary.normalize_verb(user_input);
 
     let actions: Vec<fn(&str) -> Option<Action>> = vec![
         is_exit,
+        is_undo,
+        is_redo,
+        is_again,
         is_load,
+        is_saves,
         is_save,
         is_inventory,
-        is_move,
-        is_take,
-        is_use,
     ];
 
     // Here, we iterate through a list of higher order functions and
     // effectively request the first function to return a Some(). This uses the
     // filter_map to lazily collapse the list to a function that returns a
     // Some(), and then just request the first one.
-    actions
+    let action = actions
         .iter()
-        .filter_map(|f| f(trimmed))
+        .filter_map(|f| f(&normalized))
         .next()
-        .unwrap_or(Action::Unknown)
+        .or_else(|| command(&normalized, vocabulary).map(Action::from))
+        .or_else(|| vocabulary.direction(&normalized).map(Action::Move))
+        .unwrap_or_else(|| Action::Unknown(explain(&normalized, vocabulary)));
+
+    match action {
+        Action::Move(direction) => Action::Move(
+            vocabulary
+                .direction(&direction)
+                .unwrap_or_else(|| direction.to_lowercase()),
+        ),
+        other => other,
+    }
+}
+
+/// Split a line of input into the commands it holds and parse each of them.
+/// Commands are separated by full stops, commas or "then", as in "go west,
+/// take key then go east". An "and" either starts another command or, when
+/// what follows is not a command, names another object for the previous
+/// verb, so "take key and tinderbox" takes both.
+///
+/// # Arguments
+///
+/// * `user_input` - the line typed by the player
+/// * `vocabulary` - the words the player can use for verbs and directions
+pub fn parse_commands(user_input: &str, vocabulary: &Vocabulary) -> Vec<Action> {
+    let mut actions: Vec<Action> = Vec::new();
+
+    for segment in split_on(&separate_punctuation(user_input), &[",", "then"]) {
+        let objects = match parse_input(&segment, vocabulary) {
+            Action::Put(subject, target) => Some((subject, target, Action::Put as fn(_, _) -> _)),
+            Action::TakeFrom(subject, container) => {
+                Some((subject, container, Action::TakeFrom as fn(_, _) -> _))
+            }
+            _ => None,
+        };
+        if let Some((subject, place, action)) = objects {
+            let subjects = split_on(&subject, &["and"]);
+            if subjects.len() > 1 {
+                actions.extend(
+                    subjects
+                        .into_iter()
+                        .map(|subject| action(subject, place.clone())),
+                );
+                continue;
+            }
+        }
+
+        for piece in split_on(&segment, &["and"]) {
+            let action = match (parse_input(&piece, vocabulary), actions.last()) {
+                (Action::Unknown(err), Some(previous)) => {
+                    with_object(previous, &piece).unwrap_or(Action::Unknown(err))
+                }
+                (action, _) => action,
+            };
+            actions.push(action);
+        }
+    }
+
+    if actions.is_empty() {
+        actions.push(Action::Unknown(ParseError::Empty));
+    }
+    actions
+}
+
+/// Turns each full stop or comma that ends a word into a separate ",", so
+/// that one inside a word, such as the save name in "save my.game", stays put
+fn separate_punctuation(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        let ends_word = chars.peek().is_none_or(|next| next.is_whitespace());
+        if (c == '.' || c == ',') && ends_word {
+            output.push_str(" , ");
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// Works out why input that no parser matched was not understood
+///
+/// # Arguments
+///
+/// * `normalized` - the input, with its verb rewritten by the vocabulary
+/// * `vocabulary` - the words the player can use, to suggest from
+fn explain(normalized: &str, vocabulary: &Vocabulary) -> ParseError {
+    match normalized.split_whitespace().next() {
+        Some(verb) if vocabulary.is_verb(verb) => ParseError::Misused {
+            verb: verb.to_lowercase(),
+        },
+        Some(word) => ParseError::UnknownWord {
+            word: word.to_lowercase(),
+            suggestion: vocabulary.suggest(word),
+        },
+        None => ParseError::Empty,
+    }
+}
+
+/// Splits the input into runs of words between any of the separators,
+/// leaving out empty runs
+fn split_on(input: &str, separators: &[&str]) -> Vec<String> {
+    let mut runs = vec![Vec::new()];
+    for word in input.split_whitespace() {
+        if separators
+            .iter()
+            .any(|separator| word.eq_ignore_ascii_case(separator))
+        {
+            runs.push(Vec::new());
+        } else if let Some(run) = runs.last_mut() {
+            run.push(word);
+        }
+    }
+    runs.into_iter()
+        .filter(|run| !run.is_empty())
+        .map(|run| run.join(" "))
+        .collect()
+}
+
+/// Repeats the verb of an earlier action with another object, for commands
+/// such as the "tinderbox" of "take key and tinderbox"
+fn with_object(previous: &Action, object: &str) -> Option<Action> {
+    let object = object.to_string();
+    match previous {
+        Action::Examine(_) => Some(Action::Examine(object)),
+        Action::Take(_) => Some(Action::Take(object)),
+        Action::Drop(_) => Some(Action::Drop(object)),
+        Action::Put(_, target) => Some(Action::Put(object, target.clone())),
+        Action::TakeFrom(_, container) => Some(Action::TakeFrom(object, container.clone())),
+        _ => None,
+    }
 }
 
 fn is_exit(input: &str) -> Option<Action> {
-    let exit_parser = pair(alt((tag_no_case("exit"), tag_no_case("quit"))), space0);
+    let exit_parser = pair(tag_no_case("quit"), space0);
     let parser_result: ParserPairResult = exit_parser(input);
     match parser_result {
         Ok(_result) => Some(Action::Exit),
@@ -59,8 +428,35 @@ fn is_exit(input: &str) -> Option<Action> {
     }
 }
 
+fn is_undo(input: &str) -> Option<Action> {
+    let undo_parser = all_consuming(pair(tag_no_case("undo"), space0));
+    let parser_result: ParserPairResult = undo_parser(input);
+    match parser_result {
+        Ok(_result) => Some(Action::Undo),
+        Err(_err) => None,
+    }
+}
+
+fn is_redo(input: &str) -> Option<Action> {
+    let redo_parser = all_consuming(pair(tag_no_case("redo"), space0));
+    let parser_result: ParserPairResult = redo_parser(input);
+    match parser_result {
+        Ok(_result) => Some(Action::Redo),
+        Err(_err) => None,
+    }
+}
+
+fn is_again(input: &str) -> Option<Action> {
+    let again_parser = all_consuming(pair(tag_no_case("again"), space0));
+    let parser_result: ParserPairResult = again_parser(input);
+    match parser_result {
+        Ok(_result) => Some(Action::Again),
+        Err(_err) => None,
+    }
+}
+
 fn is_inventory(input: &str) -> Option<Action> {
-    let inv_parser = pair(alt((tag_no_case("inventory"), tag_no_case("inv"))), space0);
+    let inv_parser = pair(tag_no_case("inventory"), space0);
     let parser_result: ParserPairResult = inv_parser(input);
     match parser_result {
         Ok(_result) => Some(Action::Inventory),
@@ -69,73 +465,117 @@ fn is_inventory(input: &str) -> Option<Action> {
 }
 
 fn is_load(input: &str) -> Option<Action> {
-    let load_parser = pair(tag_no_case("load"), space0);
-    let parser_result: ParserPairResult = load_parser(input);
-    match parser_result {
-        Ok(_result) => Some(Action::Load),
-        Err(_error) => None,
-    }
+    slot_command("load", input).map(Action::Load)
 }
 
 fn is_save(input: &str) -> Option<Action> {
-    let save_parser = pair(tag_no_case("save"), space0);
-    let parser_result: ParserPairResult = save_parser(input);
+    slot_command("save", input).map(Action::Save)
+}
+
+fn is_saves(input: &str) -> Option<Action> {
+    let saves_parser = all_consuming(pair(tag_no_case("saves"), space0));
+    let parser_result: ParserPairResult = saves_parser(input);
     match parser_result {
-        Ok(_result) => Some(Action::Save),
+        Ok(_result) => Some(Action::Saves),
         Err(_error) => None,
     }
 }
 
-fn is_move(input: &str) -> Option<Action> {
-    let move_parser = separated_pair(
-        alt((tag_no_case("move"), tag_no_case("go"))),
-        space1,
-        alpha1,
-    );
-
-    let parser_result: ParserPairResult = move_parser(input);
+/// Recognizes a command on a save slot, with or without the slot's name
+///
+/// # Arguments
+///
+/// * `verb` - the command, e.g. "save"
+/// * `input` - the user input
+fn slot_command(verb: &str, input: &str) -> Option<Option<String>> {
+    let named_parser = all_consuming(separated_pair(tag_no_case(verb), space1, is_not(" \t")));
+    let parser_result: ParserPairResult = named_parser(input);
+    if let Ok(res) = parser_result {
+        return Some(Some(deconstruct_pair_result(res)));
+    }
 
+    let bare_parser = all_consuming(pair(tag_no_case(verb), space0));
+    let parser_result: ParserPairResult = bare_parser(input);
     match parser_result {
-        Ok(res) => Some(Action::Move(deconstruct_pair_result(res))),
-        Err(_err) => None,
+        Ok(_result) => Some(None),
+        Err(_error) => None,
     }
 }
 
-fn is_take(input: &str) -> Option<Action> {
-    let take_parser = separated_pair(
-        alt((tag_no_case("take"), tag_no_case("get"))),
-        space1,
-        alpha1,
+/// Breaks input starting with a verb that acts on the world into a
+/// `Command`, leaving out any articles before its objects
+///
+/// # Arguments
+///
+/// * `input` - the user input, with its verb rewritten by the vocabulary
+/// * `vocabulary` - the words the player can use, to recognize verbs by
+fn command(input: &str, vocabulary: &Vocabulary) -> Option<Command> {
+    let verb = verify(is_not(" \t"), |verb: &str| {
+        vocabulary.is_verb(verb) && !GAME_VERBS.contains(&verb.to_lowercase().as_str())
+    });
+    let preposition = verify(is_not(" \t"), |word: &str| is_one_of(word, &PREPOSITIONS));
+    let indirect = pair(
+        preposition,
+        opt(preceded(space1, object_phrase(&PREPOSITIONS))),
     );
+    let command_parser = all_consuming(tuple((
+        verb,
+        opt(preceded(space1, object_phrase(&PREPOSITIONS))),
+        opt(preceded(space1, indirect)),
+        space0,
+    )));
 
-    let parser_result: ParserPairResult = take_parser(input);
+    let parser_result: CommandResult = command_parser(input.trim());
+    let (_remaining_input, (verb, direct, indirect, _space)) = parser_result.ok()?;
+    let (preposition, indirect) = match indirect {
+        Some((preposition, indirect)) => (Some(preposition), indirect),
+        None => (None, None),
+    };
 
-    match parser_result {
-        Ok(res) => Some(Action::Take(deconstruct_pair_result(res))),
-        Err(_err) => None,
-    }
+    Some(Command {
+        verb: verb.to_lowercase(),
+        direct: direct.and_then(without_articles),
+        preposition: preposition.map(|word| match word.to_lowercase().as_str() {
+            "into" | "inside" => "in".to_string(),
+            "onto" => "on".to_string(),
+            other => other.to_string(),
+        }),
+        indirect: indirect.and_then(without_articles),
+    })
 }
 
-fn is_use(input: &str) -> Option<Action> {
-    let use_parser = separated_pair(tag_no_case("use"), space1, alpha1);
-
-    let on_parser = separated_pair(tag_no_case("on"), space1, alpha1);
-
-    let combined_parser = separated_pair(use_parser, space1, on_parser);
+/// The phrase without the articles it starts with, or `None` if nothing is
+/// left
+fn without_articles(phrase: &str) -> Option<String> {
+    let words = phrase
+        .split_whitespace()
+        .skip_while(|word| is_one_of(word, &ARTICLES))
+        .join(" ");
+    Some(words).filter(|words| !words.is_empty())
+}
 
-    // Result<(&'<empty> str, ((&'<empty> str, &'<empty> str), (&'<empty> str, &'<empty> str)))
-    let parser_result: ParserTwoPairResult = combined_parser(input);
+fn is_one_of(word: &str, words: &[&str]) -> bool {
+    words.iter().any(|other| word.eq_ignore_ascii_case(other))
+}
 
-    match parser_result {
-        Ok(res) => {
-            let (_remaining_input, ((_use, subject), (_on, target))) = res;
-            Some(Action::Use(subject.to_string(), target.to_string()))
-        }
-        Err(_err) => None,
-    }
+/// Recognizes the name of an object, which may be several words long such
+/// as "phat loot", stopping before any of the `stop_words`.
+///
+/// # Arguments
+///
+/// * `stop_words` - words that end the phrase, e.g. the "on" in "use x on y"
+fn object_phrase<'a>(stop_words: &'a [&'a str]) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    let word = verify(is_not(" \t"), move |word: &str| {
+        !is_one_of(word, stop_words)
+    });
+    recognize(separated_nonempty_list(space1, word))
 }
 
 fn deconstruct_pair_result(result: (&str, (&str, &str))) -> String {
     let (_remaining_input, (_first, second)) = result;
     second.to_string()
 }
+
+#[cfg(test)]
+#[path = "./parser_tests.rs"]
+mod parser_tests;
@@ -0,0 +1,329 @@
+use super::{parse_commands, parse_input, Action, ParseError};
+use crate::vocabulary::Vocabulary;
+
+fn parse(input: &str) -> Action {
+    parse_input(input, &Vocabulary::default())
+}
+
+#[test]
+fn test_take_multi_word_item() {
+    let result = parse("take phat loot");
+
+    assert_eq!(result, Action::Take("phat loot".to_string()));
+}
+
+#[test]
+fn test_use_multi_word_target() {
+    let result = parse("USE rusty key ON north door\n");
+
+    assert_eq!(
+        result,
+        Action::Use {
+            verb: "use".to_string(),
+            item: "rusty key".to_string(),
+            target: "north door".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_use_without_target_asks_for_it() {
+    let result = parse("use key on");
+
+    assert_eq!(
+        result,
+        Action::Unknown(ParseError::MissingTarget {
+            verb: "use".to_string(),
+            object: "key".to_string(),
+            preposition: Some("on".to_string()),
+        })
+    );
+    assert_eq!(
+        ParseError::MissingTarget {
+            verb: "use".to_string(),
+            object: "key".to_string(),
+            preposition: Some("on".to_string()),
+        }
+        .to_string(),
+        "Use the key on what?"
+    );
+}
+
+#[test]
+fn test_drop_and_put_without_place() {
+    assert_eq!(parse("drop key"), Action::Drop("key".to_string()));
+    assert_eq!(
+        parse("put phat loot"),
+        Action::Drop("phat loot".to_string())
+    );
+}
+
+#[test]
+fn test_put_in_container() {
+    let result = parse("put gold coin into old chest");
+
+    assert_eq!(
+        result,
+        Action::Put("gold coin".to_string(), "old chest".to_string())
+    );
+}
+
+#[test]
+fn test_look_and_examine() {
+    assert_eq!(parse("look"), Action::Look);
+    assert_eq!(parse("L"), Action::Look);
+    assert_eq!(
+        parse("examine north door"),
+        Action::Examine("north door".to_string())
+    );
+    assert_eq!(parse("x key"), Action::Examine("key".to_string()));
+    assert_eq!(
+        parse("look at torches"),
+        Action::Examine("torches".to_string())
+    );
+}
+
+#[test]
+fn test_save_slots() {
+    assert_eq!(parse("save"), Action::Save(None));
+    assert_eq!(
+        parse("save before-door"),
+        Action::Save(Some("before-door".to_string()))
+    );
+    assert_eq!(
+        parse("LOAD slot_1"),
+        Action::Load(Some("slot_1".to_string()))
+    );
+    assert_eq!(parse("saves"), Action::Saves);
+    assert_eq!(
+        parse("save two words"),
+        Action::Unknown(ParseError::Misused {
+            verb: "save".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_verb_synonyms_and_directions() {
+    assert_eq!(parse("grab key"), Action::Take("key".to_string()));
+    assert_eq!(parse("pick up key"), Action::Take("key".to_string()));
+    assert_eq!(parse("put down key"), Action::Drop("key".to_string()));
+    assert_eq!(parse("walk North"), Action::Move("north".to_string()));
+    assert_eq!(parse("go n"), Action::Move("north".to_string()));
+    assert_eq!(parse("n"), Action::Move("north".to_string()));
+    assert_eq!(parse("Northward"), Action::Move("north".to_string()));
+    assert_eq!(parse("go portal"), Action::Move("portal".to_string()));
+    assert_eq!(parse("i"), Action::Inventory);
+}
+
+#[test]
+fn test_bare_directions_come_from_the_vocabulary() {
+    // Arrange
+    let mut vocabulary = Vocabulary::default();
+    vocabulary.add_direction("Portal");
+
+    // Act
+    let portal = parse_input("portal", &vocabulary);
+    let up = parse_input("U", &vocabulary);
+    let look = parse_input("look", &vocabulary);
+    let unknown = parse_input("sideways", &vocabulary);
+
+    // Assert
+    assert_eq!(portal, Action::Move("portal".to_string()));
+    assert_eq!(up, Action::Move("up".to_string()));
+    assert_eq!(look, Action::Look);
+    assert_eq!(
+        unknown,
+        Action::Unknown(ParseError::UnknownWord {
+            word: "sideways".to_string(),
+            suggestion: None,
+        })
+    );
+}
+
+#[test]
+fn test_commands_are_split_on_commas_then_and_and() {
+    // Arrange
+    let vocabulary = Vocabulary::default();
+
+    // Act
+    let sequence = parse_commands("go west, grab key then go east", &vocabulary);
+    let objects = parse_commands("take key and rusty tinderbox, lamp", &vocabulary);
+    let put = parse_commands("put coin and gem in chest", &vocabulary);
+    let verbs = parse_commands("drop key and look", &vocabulary);
+
+    // Assert
+    assert_eq!(
+        sequence,
+        vec![
+            Action::Move("west".to_string()),
+            Action::Take("key".to_string()),
+            Action::Move("east".to_string()),
+        ]
+    );
+    assert_eq!(
+        objects,
+        vec![
+            Action::Take("key".to_string()),
+            Action::Take("rusty tinderbox".to_string()),
+            Action::Take("lamp".to_string()),
+        ]
+    );
+    assert_eq!(
+        put,
+        vec![
+            Action::Put("coin".to_string(), "chest".to_string()),
+            Action::Put("gem".to_string(), "chest".to_string()),
+        ]
+    );
+    assert_eq!(verbs, vec![Action::Drop("key".to_string()), Action::Look]);
+    assert_eq!(
+        parse_commands(" , then ", &vocabulary),
+        vec![Action::Unknown(ParseError::Empty)]
+    );
+}
+
+#[test]
+fn test_again_and_full_stops() {
+    let vocabulary = Vocabulary::default();
+
+    assert_eq!(
+        parse_commands("take key. use it on door", &vocabulary),
+        vec![
+            Action::Take("key".to_string()),
+            Action::Use {
+                verb: "use".to_string(),
+                item: "it".to_string(),
+                target: "door".to_string(),
+            },
+        ]
+    );
+    assert_eq!(parse("again"), Action::Again);
+    assert_eq!(parse("G"), Action::Again);
+    assert!(!Action::Again.takes_turn());
+}
+
+#[test]
+fn test_unknown_input_is_explained() {
+    let explain = |input: &str| match parse(input) {
+        Action::Unknown(err) => err.to_string(),
+        action => panic!("{} parsed as {:?}", input, action),
+    };
+
+    assert_eq!(explain(""), "I beg your pardon?");
+    assert_eq!(explain("pick up"), "Take what?");
+    assert_eq!(explain("walk"), "Go where?");
+    assert_eq!(
+        explain("look key"),
+        "I understood 'look' but not the rest of that"
+    );
+    assert_eq!(
+        explain("tkae key"),
+        "I don't know the word 'tkae'. Did you mean 'take'?"
+    );
+    assert_eq!(
+        explain("norht"),
+        "I don't know the word 'norht'. Did you mean 'north'?"
+    );
+    assert_eq!(explain("dance"), "I don't know the word 'dance'");
+}
+
+#[test]
+fn test_command_grammar() {
+    // Arrange
+    let mut vocabulary = Vocabulary::default();
+    vocabulary.add_verb("give");
+    let use_on = |verb: &str, item: &str, target: &str| Action::Use {
+        verb: verb.to_string(),
+        item: item.to_string(),
+        target: target.to_string(),
+    };
+
+    // Act
+    let unlock = parse_input("unlock the north door with a rusty key", &vocabulary);
+    let give = parse_input("give coin to guard", &vocabulary);
+    let put = parse_input("put the key onto the table", &vocabulary);
+    let incomplete = parse_input("give coin", &vocabulary);
+    let unknown = parse_input("wave flag", &vocabulary);
+
+    // Assert
+    assert_eq!(unlock, use_on("unlock", "rusty key", "north door"));
+    assert_eq!(give, use_on("give", "coin", "guard"));
+    assert_eq!(put, Action::Put("key".to_string(), "table".to_string()));
+    assert_eq!(
+        incomplete,
+        Action::Unknown(ParseError::MissingTarget {
+            verb: "give".to_string(),
+            object: "coin".to_string(),
+            preposition: None,
+        })
+    );
+    assert_eq!(
+        Action::Unknown(ParseError::MissingObject {
+            verb: "take".to_string()
+        }),
+        parse("take the")
+    );
+    assert!(matches!(
+        unknown,
+        Action::Unknown(ParseError::UnknownWord { .. })
+    ));
+}
+
+#[test]
+fn test_container_commands() {
+    assert_eq!(
+        parse("take the coin from the chest"),
+        Action::TakeFrom("coin".to_string(), "chest".to_string())
+    );
+    assert_eq!(parse("look in bag"), Action::LookIn("bag".to_string()));
+    assert_eq!(parse("open chest"), Action::Open("chest".to_string()));
+    assert_eq!(parse("shut the chest"), Action::Close("chest".to_string()));
+}
+
+#[test]
+fn test_take_from_without_container_asks_for_it() {
+    let result = parse("take coin from");
+
+    assert_eq!(
+        result,
+        Action::Unknown(ParseError::MissingTarget {
+            verb: "take".to_string(),
+            object: "coin".to_string(),
+            preposition: Some("from".to_string()),
+        })
+    );
+    assert_eq!(
+        ParseError::MissingTarget {
+            verb: "take".to_string(),
+            object: "coin".to_string(),
+            preposition: Some("from".to_string()),
+        }
+        .to_string(),
+        "Take the coin from what?"
+    );
+}
+
+#[test]
+fn test_full_stops_inside_words_do_not_split_commands() {
+    let vocabulary = Vocabulary::default();
+
+    assert_eq!(
+        parse_commands("save my.game", &vocabulary),
+        vec![Action::Save(Some("my.game".to_string()))]
+    );
+    assert_eq!(
+        parse_commands("save ../x", &vocabulary),
+        vec![Action::Save(Some("../x".to_string()))]
+    );
+    assert_eq!(
+        parse_commands("look, save my.game.", &vocabulary),
+        vec![Action::Look, Action::Save(Some("my.game".to_string()))]
+    );
+}
+
+#[test]
+fn test_go_in_is_a_direction() {
+    assert_eq!(parse("go in"), Action::Move("in".to_string()));
+    assert_eq!(parse("go into"), Action::Move("in".to_string()));
+}
@@ -0,0 +1,204 @@
+#![warn(missing_docs)]
+
+//! The `playthrough` module replays transcripts of commands against a world
+//! and checks the game's responses, so walkthroughs of each puzzle can be
+//! kept as regression tests.
+//!
+//! A transcript is a text file of commands, each starting with `>`, followed
+//! by snippets of text the response must contain. A snippet starting with
+//! `!` must not appear in the response. Blank lines and lines starting with
+//! `#` are ignored.
+//!
+//! ```text
+//! # The north door needs the key
+//! > go north
+//! A long corridor
+//! > go north
+//! The north door is locked
+//! ! You have moved
+//! ```
+
+use std::fmt;
+use std::fs;
+
+use crate::game::Game;
+use crate::world_building::World;
+
+/// A command in a transcript and what its response should contain
+#[derive(Debug, PartialEq)]
+pub struct Step {
+    /// The line of the transcript the command is on
+    pub line: usize,
+    /// The command to give the game
+    pub command: String,
+    /// Snippets the response must contain
+    pub expected: Vec<String>,
+    /// Snippets the response must not contain
+    pub unexpected: Vec<String>,
+}
+
+/// A sequence of commands and expectations
+#[derive(Debug, PartialEq)]
+pub struct Transcript {
+    /// The steps, in the order they are played
+    pub steps: Vec<Step>,
+}
+
+impl Transcript {
+    /// Read a transcript from a file
+    ///
+    /// # Errors
+    /// The file could not be read or is not a valid transcript
+    pub fn from_file(path: &str) -> Result<Transcript, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| format!("could not read {}: {}", path, err))?;
+        Transcript::parse(&contents).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    /// Parse the text of a transcript
+    ///
+    /// # Errors
+    /// An expectation appears before the first command
+    pub fn parse(text: &str) -> Result<Transcript, String> {
+        let mut steps: Vec<Step> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(command) = line.strip_prefix('>') {
+                steps.push(Step {
+                    line: line_number,
+                    command: command.trim().to_string(),
+                    expected: Vec::new(),
+                    unexpected: Vec::new(),
+                });
+                continue;
+            }
+
+            let step = steps.last_mut().ok_or_else(|| {
+                format!(
+                    "line {}: expected output before the first command",
+                    line_number
+                )
+            })?;
+            match line.strip_prefix('!') {
+                Some(snippet) => step.unexpected.push(snippet.trim().to_string()),
+                None => step.expected.push(line.to_string()),
+            }
+        }
+
+        Ok(Transcript { steps })
+    }
+
+    /// Play the transcript in a new game of the world, checking every step
+    /// even after one has failed
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world to play in
+    pub fn run(&self, world: World) -> Report {
+        let mut game = Game::new(world);
+        let mut failures = Vec::new();
+
+        for step in &self.steps {
+            let response = game.step(&step.command);
+            let missing: Vec<String> = step
+                .expected
+                .iter()
+                .filter(|snippet| !response.text.contains(snippet.as_str()))
+                .cloned()
+                .collect();
+            let unwanted: Vec<String> = step
+                .unexpected
+                .iter()
+                .filter(|snippet| response.text.contains(snippet.as_str()))
+                .cloned()
+                .collect();
+
+            if !missing.is_empty() || !unwanted.is_empty() {
+                failures.push(Failure {
+                    line: step.line,
+                    command: step.command.clone(),
+                    missing,
+                    unwanted,
+                    actual: response.text,
+                });
+            }
+        }
+
+        Report {
+            steps: self.steps.len(),
+            failures,
+        }
+    }
+}
+
+/// A step whose response did not match the transcript
+#[derive(Debug, PartialEq)]
+pub struct Failure {
+    /// The line of the transcript the command is on
+    pub line: usize,
+    /// The command given to the game
+    pub command: String,
+    /// Expected snippets that were not in the response
+    pub missing: Vec<String>,
+    /// Snippets that should not have been in the response
+    pub unwanted: Vec<String>,
+    /// The whole response
+    pub actual: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "line {}: > {}", self.line, self.command)?;
+        for snippet in &self.missing {
+            writeln!(f, "- {}", snippet)?;
+        }
+        for snippet in &self.unwanted {
+            writeln!(f, "+ {}", snippet)?;
+        }
+        writeln!(f, "  response was:")?;
+        for line in self.actual.lines() {
+            writeln!(f, "  | {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of playing a transcript
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    /// The number of steps played
+    pub steps: usize,
+    /// The steps that did not match
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    /// Returns true if every step matched
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for failure in &self.failures {
+            writeln!(f, "{}", failure)?;
+        }
+        write!(
+            f,
+            "{} of {} steps passed",
+            self.steps - self.failures.len(),
+            self.steps
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "./playthrough_tests.rs"]
+mod playthrough_tests;
@@ -0,0 +1,75 @@
+use super::{Step, Transcript};
+use crate::world_building::World;
+use crate::DEFAULT_WORLD;
+
+#[test]
+fn test_parse_transcript() {
+    // Arrange
+    let text = "# comment\n> go north\nA long corridor\n! You cannot\n\n> inv\n";
+
+    // Act
+    let result = Transcript::parse(text).unwrap();
+
+    // Assert
+    assert_eq!(
+        result.steps,
+        vec![
+            Step {
+                line: 2,
+                command: "go north".to_string(),
+                expected: vec!["A long corridor".to_string()],
+                unexpected: vec!["You cannot".to_string()],
+            },
+            Step {
+                line: 6,
+                command: "inv".to_string(),
+                expected: vec![],
+                unexpected: vec![],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_rejects_output_before_command() {
+    let result = Transcript::parse("A long corridor\n> go north");
+
+    assert_eq!(
+        result.unwrap_err(),
+        "line 1: expected output before the first command"
+    );
+}
+
+#[test]
+fn test_run_reports_differences() {
+    // Arrange
+    let transcript =
+        Transcript::parse("> go north\nA long corridor\n> go north\nYou have moved\n! locked")
+            .unwrap();
+    let world = World::from_file(DEFAULT_WORLD).unwrap();
+
+    // Act
+    let report = transcript.run(world);
+
+    // Assert
+    assert!(!report.passed());
+    assert_eq!(report.failures.len(), 1);
+    let failure = &report.failures[0];
+    assert_eq!(failure.line, 3);
+    assert_eq!(failure.missing, vec!["You have moved"]);
+    assert_eq!(failure.unwanted, vec!["locked"]);
+    assert_eq!(
+        failure.to_string(),
+        "line 3: > go north\n- You have moved\n+ locked\n  response was:\n  | The north door is locked\n"
+    );
+}
+
+#[test]
+fn test_shipped_walkthrough_passes() {
+    let transcript = Transcript::from_file("worlds/dungeon.walkthrough").unwrap();
+    let world = World::from_file(DEFAULT_WORLD).unwrap();
+
+    let report = transcript.run(world);
+
+    assert!(report.passed(), "{}", report);
+}
@@ -0,0 +1,517 @@
+#![warn(missing_docs)]
+
+//! The `resolver` module matches the object phrases typed by the player,
+//! such as "loot", against the things the player can see, turning them into
+//! the ids every other part of the game looks things up by.
+
+use itertools::Itertools;
+use std::iter;
+use std::mem;
+
+use crate::entities::interactive::Effect;
+use crate::entities::{Feature, Item, Room};
+use crate::errors::{GameError, GameResult};
+use crate::parser::{Action, ParseError, ARTICLES};
+use crate::vocabulary;
+use crate::world_building::World;
+
+/// The words the player can use to refer to an item or feature
+#[derive(Debug, Clone, PartialEq, new)]
+pub struct Noun {
+    /// The id of the thing
+    pub id: String,
+    /// The name shown to the player
+    pub name: String,
+    /// Other names for the thing
+    pub aliases: Vec<String>,
+    /// Words that pick out the thing among others of the same name
+    pub adjectives: Vec<String>,
+}
+
+impl Noun {
+    /// Returns true if the phrase is the thing's id, or its name or one of
+    /// its aliases after any number of its adjectives
+    fn is_called(&self, phrase: &[String]) -> bool {
+        if phrase.join(" ") == self.id.to_lowercase() {
+            return true;
+        }
+        let names = self.names().map(|name| words(name)).collect_vec();
+        (0..phrase.len()).any(|start| {
+            phrase[..start].iter().all(|word| self.has_adjective(word))
+                && names.iter().any(|name| name[..] == phrase[start..])
+        })
+    }
+
+    /// Returns true if every word of the phrase is one of the thing's
+    /// adjectives or part of its name or an alias
+    fn is_described_by(&self, phrase: &[String]) -> bool {
+        phrase.iter().all(|word| {
+            self.has_adjective(word) || self.names().any(|name| words(name).contains(word))
+        })
+    }
+
+    /// Every word the thing can be referred to by, in lower case
+    fn vocabulary(&self) -> impl Iterator<Item = String> + '_ {
+        iter::once(&self.id)
+            .chain(self.names())
+            .chain(&self.adjectives)
+            .flat_map(|phrase| words(phrase))
+    }
+
+    fn names(&self) -> impl Iterator<Item = &String> {
+        iter::once(&self.name).chain(&self.aliases)
+    }
+
+    fn has_adjective(&self, word: &str) -> bool {
+        self.adjectives.iter().any(|a| a.eq_ignore_ascii_case(word))
+    }
+}
+
+/// The outcome of matching a phrase against a set of things
+#[derive(Debug, PartialEq)]
+pub enum Resolution {
+    /// Exactly one thing matched, giving its id
+    Found(String),
+    /// Several things matched equally well, ordered by name
+    Ambiguous(Vec<Noun>),
+    /// Nothing matched
+    NotFound,
+}
+
+/// Match a phrase against a set of things, ignoring case and spacing. A
+/// thing matches exactly if the phrase is its id, name or an alias, possibly
+/// after some of its adjectives, and partially if every word of the phrase is
+/// one the thing is known by. Exact matches always win over partial ones.
+///
+/// # Arguments
+///
+/// * `phrase` - the words typed by the player, e.g. "loot"
+/// * `nouns` - the candidate things, e.g. the "phat loot"
+pub fn resolve(phrase: &str, nouns: impl IntoIterator<Item = Noun>) -> Resolution {
+    let phrase_words = words(phrase);
+    if phrase_words.is_empty() {
+        return Resolution::NotFound;
+    }
+
+    let mut exact = Vec::new();
+    let mut partial = Vec::new();
+    for noun in nouns {
+        if noun.is_called(&phrase_words) {
+            exact.push(noun);
+        } else if noun.is_described_by(&phrase_words) {
+            partial.push(noun);
+        }
+    }
+
+    let mut matches = if exact.is_empty() { partial } else { exact };
+    matches.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    matches.dedup_by(|a, b| a.id == b.id);
+    match matches.len() {
+        0 => Resolution::NotFound,
+        1 => Resolution::Found(matches.remove(0).id),
+        _ => Resolution::Ambiguous(matches),
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(|w| w.to_lowercase()).collect()
+}
+
+/// A command whose object phrase matched more than one thing, waiting for
+/// the player to say which one they meant
+#[derive(Debug)]
+pub struct Clarification {
+    action: Action,
+    phrase: String,
+    choices: Vec<Noun>,
+}
+
+impl Clarification {
+    /// The question to put to the player
+    pub fn question(&self) -> String {
+        let choices = self
+            .choices
+            .iter()
+            .map(|c| format!("the {}", c.name))
+            .collect_vec();
+        let (last, rest) = choices.split_last().expect("a clarification has choices");
+        format!("Which do you mean, {} or {}?", rest.join(", "), last)
+    }
+
+    /// Completes the original command if the player's input picks out one of
+    /// the choices, e.g. "phat" or "the phat loot".
+    ///
+    /// # Arguments
+    ///
+    /// * `user_input` - the player's reply
+    pub fn answer(&self, user_input: &str) -> Option<Action> {
+        let reply = user_input.trim();
+        let reply = reply
+            .strip_prefix("the ")
+            .or_else(|| reply.strip_prefix("The "))
+            .unwrap_or(reply);
+        match resolve(reply, self.choices.iter().cloned()) {
+            Resolution::Found(id) => Some(replace_object(&self.action, &self.phrase, &id)),
+            _ => None,
+        }
+    }
+}
+
+/// The objects the player referred to most recently, which "it" and "them"
+/// stand for in later commands
+#[derive(Debug, Default)]
+pub struct Referents {
+    /// The ids of the objects, the most recent last
+    objects: Vec<String>,
+    /// The kind of action that referred to them
+    verb: Option<mem::Discriminant<Action>>,
+}
+
+impl Referents {
+    /// Remembers the object of an action once it has been resolved. The
+    /// objects of a run of actions with the same verb, such as "take key and
+    /// tinderbox", are remembered together.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - the resolved action
+    pub fn remember(&mut self, action: &Action) {
+        let object = match objects(action).first() {
+            Some(object) => object.to_string(),
+            None => return,
+        };
+
+        let verb = Some(mem::discriminant(action));
+        if self.verb != verb {
+            self.objects.clear();
+            self.verb = verb;
+        }
+        if !self.objects.contains(&object) {
+            self.objects.push(object);
+        }
+    }
+
+    /// Replaces "it" and "them" in an action with the objects they stand
+    /// for. "It" is the object referred to last, and "them" as the object of
+    /// the action gives an action for each of the objects remembered. Returns
+    /// `None` if the action has no pronouns.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - the action parsed from the player's input
+    ///
+    /// # Errors
+    /// The player has not referred to anything yet
+    pub fn substitute(&self, action: &Action) -> Option<GameResult<Vec<Action>>> {
+        let phrases = objects(action);
+        let pronoun = phrases.iter().find(|phrase| is_pronoun(phrase))?;
+        let last = match self.objects.last() {
+            Some(last) => last,
+            None => {
+                return Some(Err(GameError::Refused(format!(
+                    "I don't know what \"{}\" refers to",
+                    pronoun.to_lowercase()
+                ))))
+            }
+        };
+
+        let them = match phrases.first() {
+            Some(first) if first.eq_ignore_ascii_case("them") => self.objects.clone(),
+            _ => vec![last.clone()],
+        };
+        Some(Ok(them
+            .iter()
+            .map(|object| {
+                map_objects(action, |phrase| match phrase.to_lowercase().as_str() {
+                    "it" => last.clone(),
+                    "them" => object.clone(),
+                    _ => phrase.to_string(),
+                })
+            })
+            .collect()))
+    }
+}
+
+fn is_pronoun(phrase: &str) -> bool {
+    phrase.eq_ignore_ascii_case("it") || phrase.eq_ignore_ascii_case("them")
+}
+
+/// Resolve the object phrases of an action against what the player can see:
+/// the items in the room for `take`, the player's inventory for the thing
+/// being dropped, put or used, the room's features for what an item is used
+/// on, and anything in reach for the place something is put or the thing
+/// being examined.
+/// Phrases that match are replaced by the id of what they matched, and
+/// phrases that match nothing are left alone so the action can report the
+/// missing object itself.
+///
+/// # Arguments
+///
+/// * `world` - the current world state
+/// * `action` - the action parsed from the player's input
+///
+/// # Errors
+/// A phrase matched several objects, and the player must choose between them
+pub fn resolve_action(world: &World, action: Action) -> Result<Action, Box<Clarification>> {
+    let room = match world.locations.get(&world.player_location) {
+        Some(room) => room,
+        None => return Ok(action),
+    };
+
+    match &action {
+        Action::Take(item_name) => {
+            let item_name = item_name.clone();
+            let carried = |item: &&Item| world.player.has_item(&item.id);
+            let takeable = visible_items(world).filter(|item| !carried(item));
+            let nouns = features(room).chain(takeable.map(Item::noun));
+            resolve_in(action, &item_name, nouns)
+        }
+        Action::TakeFrom(item_name, container) => {
+            let (item_name, container) = (item_name.clone(), container.clone());
+            let action = resolve_in(action, &container, in_reach(world, room))?;
+            let contents = match &action {
+                Action::TakeFrom(_item, container) => contents_of(world, room, container),
+                _ => Vec::new(),
+            };
+            resolve_in(action, &item_name, contents)
+        }
+        Action::Open(name) | Action::Close(name) | Action::LookIn(name) => {
+            let name = name.clone();
+            resolve_in(action, &name, in_reach(world, room))
+        }
+        Action::Drop(item_name) => {
+            let item_name = item_name.clone();
+            resolve_in(action, &item_name, inventory(world))
+        }
+        Action::Put(item_name, target) => {
+            let (item_name, target) = (item_name.clone(), target.clone());
+            let action = resolve_in(action, &item_name, inventory(world))?;
+            resolve_in(action, &target, in_reach(world, room))
+        }
+        Action::Examine(name) => {
+            let name = name.clone();
+            resolve_in(action, &name, in_reach(world, room))
+        }
+        Action::Use { item, target, .. } => {
+            let (item, target) = (item.clone(), target.clone());
+            let action = resolve_in(action, &item, inventory(world))?;
+            resolve_in(action, &target, in_reach(world, room))
+        }
+        _ => Ok(action),
+    }
+}
+
+/// Finds a word in the object phrases of an action that nothing in the world
+/// is called, so the player can be told the word is not understood rather
+/// than that the thing is not here. Hidden objects do not count, so their
+/// words give nothing away. A close match is suggested if there is one.
+///
+/// # Arguments
+///
+/// * `world` - the current world state
+/// * `action` - the resolved action
+pub fn unknown_word(world: &World, action: &Action) -> Option<ParseError> {
+    let known = known_nouns(world)
+        .iter()
+        .flat_map(Noun::vocabulary)
+        .collect_vec();
+
+    objects(action)
+        .into_iter()
+        .flat_map(words)
+        .find(|word| !ARTICLES.contains(&word.as_str()) && !known.contains(word))
+        .map(|word| ParseError::UnknownWord {
+            suggestion: vocabulary::closest(&word, known.iter().map(String::as_str)),
+            word,
+        })
+}
+
+/// Turns a word the parser did not know into a question about the thing it
+/// names, if anything in the world goes by that word, so that "bag" on its
+/// own asks what to do with the bag rather than claiming not to know it
+///
+/// # Arguments
+///
+/// * `world` - the current world state
+/// * `error` - why the parser did not understand the input
+pub fn object_without_verb(world: &World, error: &ParseError) -> Option<ParseError> {
+    let word = match error {
+        ParseError::UnknownWord { word, .. } => word,
+        _ => return None,
+    };
+    let names = known_nouns(world)
+        .into_iter()
+        .filter(|noun| noun.vocabulary().any(|known| known == *word))
+        .map(|noun| noun.name)
+        .unique()
+        .collect_vec();
+    match names.as_slice() {
+        [] => None,
+        [name] => Some(ParseError::NoVerb {
+            object: name.clone(),
+        }),
+        _ => Some(ParseError::NoVerb {
+            object: word.clone(),
+        }),
+    }
+}
+
+/// The words for every item and feature in the world or that rules can
+/// create, leaving out hidden ones
+fn known_nouns(world: &World) -> Vec<Noun> {
+    let effects = world.rules.iter().flat_map(|rule| &rule.effects);
+    let features = world
+        .locations
+        .values()
+        .flat_map(|room| &room.features)
+        .chain(effects.clone().filter_map(|effect| match effect {
+            Effect::ReplaceFeature { with, .. } => Some(with),
+            _ => None,
+        }))
+        .filter(|feature| !feature.properties.hidden)
+        .collect_vec();
+    let items = world
+        .locations
+        .values()
+        .flat_map(|room| &room.items)
+        .chain(&world.player.inventory)
+        .chain(
+            features
+                .iter()
+                .filter_map(|feature| feature.container.as_ref())
+                .flat_map(|container| &container.contents),
+        )
+        .chain(effects.filter_map(|effect| match effect {
+            Effect::SpawnItem(item) => Some(item),
+            _ => None,
+        }));
+
+    let mut nouns = features.iter().map(|feature| feature.noun()).collect_vec();
+    items.for_each(|item| item_nouns(item, &mut nouns));
+    nouns
+}
+
+/// Adds the words for an item, and anything inside it, unless it is hidden
+fn item_nouns(item: &Item, nouns: &mut Vec<Noun>) {
+    if item.properties.hidden {
+        return;
+    }
+    nouns.push(item.noun());
+    if let Some(container) = &item.container {
+        for inner in &container.contents {
+            item_nouns(inner, nouns);
+        }
+    }
+}
+
+/// The things the player is carrying
+fn inventory(world: &World) -> impl Iterator<Item = Noun> + '_ {
+    world.player.inventory.iter().map(Item::noun)
+}
+
+/// The room's features that are not hidden
+fn features(room: &Room) -> impl Iterator<Item = Noun> + '_ {
+    room.features
+        .iter()
+        .filter(|feature| !feature.properties.hidden)
+        .map(Feature::noun)
+}
+
+/// The room's features and the items the player can see
+fn in_reach<'a>(world: &'a World, room: &'a Room) -> impl Iterator<Item = Noun> + 'a {
+    features(room).chain(visible_items(world).map(Item::noun))
+}
+
+/// The items in the room and inventory, and inside open or transparent
+/// containers
+fn visible_items(world: &World) -> impl Iterator<Item = &Item> {
+    world.visible_items().unwrap_or_default().into_iter()
+}
+
+/// The things that can be seen inside a feature or item, by id
+fn contents_of(world: &World, room: &Room, id: &str) -> Vec<Noun> {
+    room.features
+        .iter()
+        .filter(|feature| feature.id == id && !feature.properties.hidden)
+        .filter_map(|feature| feature.container.as_ref())
+        .chain(
+            visible_items(world)
+                .filter(|item| item.id == id)
+                .filter_map(|item| item.container.as_ref()),
+        )
+        .filter(|container| container.shows_contents())
+        .flat_map(|container| container.contents.iter().map(Item::noun))
+        .collect()
+}
+
+fn resolve_in(
+    action: Action,
+    phrase: &str,
+    nouns: impl IntoIterator<Item = Noun>,
+) -> Result<Action, Box<Clarification>> {
+    match resolve(phrase, nouns) {
+        Resolution::Found(id) => Ok(replace_object(&action, phrase, &id)),
+        Resolution::NotFound => Ok(action),
+        Resolution::Ambiguous(choices) => Err(Box::new(Clarification {
+            action,
+            phrase: phrase.to_string(),
+            choices,
+        })),
+    }
+}
+
+fn replace_object(action: &Action, phrase: &str, id: &str) -> Action {
+    map_objects(action, |object| {
+        if object == phrase {
+            id.to_string()
+        } else {
+            object.to_string()
+        }
+    })
+}
+
+/// The object phrases of an action, the direct object first
+fn objects(action: &Action) -> Vec<&str> {
+    match action {
+        Action::Take(object)
+        | Action::Drop(object)
+        | Action::Examine(object)
+        | Action::Open(object)
+        | Action::Close(object)
+        | Action::LookIn(object) => vec![object],
+        Action::TakeFrom(object, container) => vec![object, container],
+        Action::Put(object, target)
+        | Action::Use {
+            item: object,
+            target,
+            ..
+        } => vec![object, target],
+        _ => vec![],
+    }
+}
+
+/// Rebuilds an action with each of its object phrases changed
+fn map_objects(action: &Action, change: impl Fn(&str) -> String) -> Action {
+    match action {
+        Action::Take(item_name) => Action::Take(change(item_name)),
+        Action::Drop(item_name) => Action::Drop(change(item_name)),
+        Action::Examine(name) => Action::Examine(change(name)),
+        Action::TakeFrom(item_name, container) => {
+            Action::TakeFrom(change(item_name), change(container))
+        }
+        Action::Open(name) => Action::Open(change(name)),
+        Action::Close(name) => Action::Close(change(name)),
+        Action::LookIn(name) => Action::LookIn(change(name)),
+        Action::Put(item_name, target) => Action::Put(change(item_name), change(target)),
+        Action::Use { verb, item, target } => Action::Use {
+            verb: verb.clone(),
+            item: change(item),
+            target: change(target),
+        },
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./resolver_tests.rs"]
+mod resolver_tests;
@@ -0,0 +1,126 @@
+use super::{resolve, resolve_action, Noun, Referents, Resolution};
+use crate::entities::{Item, Room};
+use crate::parser::Action;
+use crate::world_building::World;
+
+fn nouns(names: &[&str]) -> Vec<Noun> {
+    names
+        .iter()
+        .map(|name| Item::new(name.to_string()).noun())
+        .collect()
+}
+
+#[test]
+fn test_resolve_partial_match() {
+    let result = resolve("loot", nouns(&["key", "phat loot"]));
+
+    assert_eq!(result, Resolution::Found("phat loot".to_string()));
+}
+
+#[test]
+fn test_resolve_exact_match_beats_partial() {
+    let result = resolve("Key", nouns(&["rusty key", "key"]));
+
+    assert_eq!(result, Resolution::Found("key".to_string()));
+}
+
+#[test]
+fn test_resolve_ambiguous() {
+    let result = resolve("key", nouns(&["rusty key", "gold key", "tinderbox"]));
+
+    assert_eq!(
+        result,
+        Resolution::Ambiguous(nouns(&["gold key", "rusty key"]))
+    );
+}
+
+#[test]
+fn test_resolve_aliases_and_adjectives() {
+    // Arrange
+    let mut crown = Item::new("phat loot".to_string());
+    crown.id = "loot".to_string();
+    crown.aliases = vec!["treasure".to_string()];
+    crown.adjectives = vec!["shiny".to_string()];
+    let mut key = Item::new("key".to_string());
+    key.adjectives = vec!["iron".to_string()];
+
+    // Act
+    let by_alias = resolve("Shiny TREASURE", vec![crown.noun(), key.noun()]);
+    let by_adjective = resolve("iron", vec![crown.noun(), key.noun()]);
+    let by_name = resolve("phat loot", vec![crown.noun(), key.noun()]);
+    let unknown = resolve("shiny key", vec![crown.noun(), key.noun()]);
+
+    // Assert
+    assert_eq!(by_alias, Resolution::Found("loot".to_string()));
+    assert_eq!(by_adjective, Resolution::Found("key".to_string()));
+    assert_eq!(by_name, Resolution::Found("loot".to_string()));
+    assert_eq!(unknown, Resolution::NotFound);
+}
+
+#[test]
+fn test_resolve_action_asks_which_and_accepts_answer() {
+    // Arrange
+    let mut room = Room::new("vault".to_string(), "A vault".to_string());
+    room.add_item(Item::new("rusty key".to_string()));
+    room.add_item(Item::new("gold key".to_string()));
+    let mut world = World {
+        player_location: "vault".to_string(),
+        ..World::default()
+    };
+    world.add_location(room);
+
+    // Act
+    let clarification = resolve_action(&world, Action::Take("key".to_string())).unwrap_err();
+    let answer = clarification.answer("the gold one");
+    let retry = clarification.answer("gold");
+
+    // Assert
+    assert_eq!(
+        clarification.question(),
+        "Which do you mean, the gold key or the rusty key?"
+    );
+    assert_eq!(answer, None);
+    assert_eq!(retry, Some(Action::Take("gold key".to_string())));
+}
+
+#[test]
+fn test_pronouns_stand_for_the_objects_referred_to_last() {
+    // Arrange
+    let mut referents = Referents::default();
+    let take = |id: &str| Action::Take(id.to_string());
+    let unknown_yet = referents.substitute(&Action::Drop("it".to_string()));
+    referents.remember(&take("key"));
+    referents.remember(&take("tinderbox"));
+    referents.remember(&Action::Look);
+
+    // Act
+    let it = referents.substitute(&Action::Use {
+        verb: "use".to_string(),
+        item: "It".to_string(),
+        target: "door".to_string(),
+    });
+    let them = referents.substitute(&Action::Put("them".to_string(), "it".to_string()));
+    let none = referents.substitute(&take("lamp"));
+
+    // Assert
+    assert_eq!(
+        unknown_yet.unwrap().unwrap_err().to_string(),
+        "I don't know what \"it\" refers to"
+    );
+    assert_eq!(
+        it.unwrap().unwrap(),
+        vec![Action::Use {
+            verb: "use".to_string(),
+            item: "tinderbox".to_string(),
+            target: "door".to_string(),
+        }]
+    );
+    assert_eq!(
+        them.unwrap().unwrap(),
+        vec![
+            Action::Put("key".to_string(), "tinderbox".to_string()),
+            Action::Put("tinderbox".to_string(), "tinderbox".to_string()),
+        ]
+    );
+    assert!(none.is_none());
+}
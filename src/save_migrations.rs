@@ -0,0 +1,195 @@
+#![warn(missing_docs)]
+
+//! The `save_migrations` module upgrades save files written by older
+//! versions of the game to the current format.
+//!
+//! Each migration upgrades a save by one version, so a save of any age is
+//! brought up to date by running the migrations after its version in order.
+//! When the save format changes, bump `CURRENT_VERSION` and add a migration
+//! to the end of `MIGRATIONS`.
+
+use serde_json::{json, Value};
+
+use crate::errors::{GameError, GameResult};
+
+/// The version of the save format written by this version of the game
+pub const CURRENT_VERSION: u64 = 4;
+
+/// Upgrades a save from the version at its index to the next version
+type Migration = fn(Value, &str) -> GameResult<Value>;
+
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] =
+    [wrap_bare_world, add_envelope, extract_state, names_to_ids];
+
+/// Upgrade a save to the current format
+///
+/// # Arguments
+///
+/// * `save` - the contents of the save file
+/// * `name` - the name of the save slot, for saves that did not record it
+///
+/// # Errors
+/// The save was made by a newer version of the game or is malformed
+pub fn migrate(mut save: Value, name: &str) -> GameResult<Value> {
+    let version = version_of(&save);
+    if version > CURRENT_VERSION {
+        return Err(GameError::Refused(format!(
+            "{} was saved by a newer version of the game (format {})",
+            name, version
+        )));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!("Migrating save {} from format {}", name, from);
+        save = migration(save, name)?;
+    }
+    Ok(save)
+}
+
+/// Works out the version of a save, including those from before saves
+/// recorded their version
+fn version_of(save: &Value) -> u64 {
+    match save.get("version").and_then(Value::as_u64) {
+        Some(version) => version,
+        None if save.get("metadata").is_some() => 1,
+        None => 0,
+    }
+}
+
+/// Version 0 saves were a bare dump of the `World`, without metadata
+fn wrap_bare_world(world: Value, name: &str) -> GameResult<Value> {
+    let location = world
+        .get("player_location")
+        .cloned()
+        .ok_or_else(|| not_a_save(name))?;
+    Ok(json!({
+        "metadata": {
+            "name": name,
+            "saved_at": 0,
+            "location": location,
+            "turns": world.get("turns").cloned().unwrap_or_else(|| json!(0)),
+            "world_id": world.get("id").cloned().unwrap_or_else(|| json!("")),
+        },
+        "world": world,
+    }))
+}
+
+/// Version 1 saves had metadata but no version or world hash
+fn add_envelope(mut save: Value, name: &str) -> GameResult<Value> {
+    let envelope = save.as_object_mut().ok_or_else(|| not_a_save(name))?;
+    envelope.insert("version".to_string(), json!(2));
+    envelope.insert("world_hash".to_string(), json!(""));
+    Ok(save)
+}
+
+/// Version 2 saves held the whole `World`. The state of every room is kept,
+/// as there is no definition to compare it with here, which restores the
+/// game just as a version 3 save would.
+fn extract_state(mut save: Value, name: &str) -> GameResult<Value> {
+    let not_a_save = || not_a_save(name);
+    let envelope = save.as_object_mut().ok_or_else(not_a_save)?;
+    let world = envelope.remove("world").ok_or_else(not_a_save)?;
+
+    let rooms: serde_json::Map<String, Value> = world
+        .get("locations")
+        .and_then(Value::as_object)
+        .ok_or_else(not_a_save)?
+        .iter()
+        .map(|(id, room)| {
+            let exits: serde_json::Map<String, Value> = room
+                .get("exits")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .map(|(direction, exit)| (direction.clone(), exit_state(exit)))
+                .collect();
+            let state = json!({
+                "items": object_states(room.get("items")),
+                "features": object_states(room.get("features")),
+                "exits": exits,
+            });
+            (id.clone(), state)
+        })
+        .collect();
+
+    let state = json!({
+        "player_location": world.get("player_location").cloned().ok_or_else(not_a_save)?,
+        "turns": world.get("turns").cloned().unwrap_or_else(|| json!(0)),
+        "flags": world.get("flags").cloned().unwrap_or_else(|| json!({})),
+        "inventory": object_states(world.pointer("/player/inventory")),
+        "rooms": rooms,
+    });
+    envelope.insert("version".to_string(), json!(3));
+    envelope.insert("state".to_string(), state);
+    Ok(save)
+}
+
+/// Reduces a list of saved items or features to their names and contents
+fn object_states(objects: Option<&Value>) -> Value {
+    let states: Vec<Value> = objects
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|object| {
+            json!({
+                "name": object.get("name").cloned().unwrap_or_else(|| json!("")),
+                "contents": object_states(object.pointer("/container/contents")),
+            })
+        })
+        .collect();
+    Value::Array(states)
+}
+
+/// Exits were saved as a destination before they could be locked
+fn exit_state(exit: &Value) -> Value {
+    match exit {
+        Value::String(destination) => json!({ "destination": destination, "locked": false }),
+        _ => json!({
+            "destination": exit.get("destination").cloned().unwrap_or_else(|| json!("")),
+            "locked": exit.get("locked").cloned().unwrap_or(Value::Bool(false)),
+        }),
+    }
+}
+
+/// Version 3 saves recorded items and features by name, before they had
+/// ids. The ids they would have been given are their names in lower case.
+fn names_to_ids(mut save: Value, name: &str) -> GameResult<Value> {
+    let state = save
+        .get_mut("state")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| not_a_save(name))?;
+
+    if let Some(inventory) = state.get_mut("inventory") {
+        rename_to_ids(inventory);
+    }
+    if let Some(rooms) = state.get_mut("rooms").and_then(Value::as_object_mut) {
+        for room in rooms.values_mut() {
+            for list in &["items", "features"] {
+                if let Some(objects) = room.get_mut(*list) {
+                    rename_to_ids(objects);
+                }
+            }
+        }
+    }
+    save["version"] = json!(4);
+    Ok(save)
+}
+
+/// Replaces the names of saved items or features, and anything inside them,
+/// by ids
+fn rename_to_ids(objects: &mut Value) {
+    for object in objects.as_array_mut().into_iter().flatten() {
+        if let Some(fields) = object.as_object_mut() {
+            if let Some(Value::String(name)) = fields.remove("name") {
+                fields.insert("id".to_string(), json!(name.to_lowercase()));
+            }
+            if let Some(contents) = fields.get_mut("contents") {
+                rename_to_ids(contents);
+            }
+        }
+    }
+}
+
+fn not_a_save(name: &str) -> GameError {
+    GameError::Serialization(format!("{} is not a saved game", name))
+}
@@ -0,0 +1,277 @@
+#![warn(missing_docs)]
+
+//! The `saves` module stores games in named slots, one file per slot in a
+//! save directory, along with details of each save for listing.
+//!
+//! A save holds only how the game differs from the world definition, see
+//! `world_state`, so loading it rebuilds the game from the definition.
+
+use itertools::Itertools;
+use log_derive::logfn;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::{GameError, GameResult};
+use crate::save_migrations::{self, CURRENT_VERSION};
+use crate::world_building::World;
+use crate::world_state::WorldState;
+
+/// The directory saves are kept in when none is configured
+pub const DEFAULT_SAVE_DIR: &str = "saves";
+
+/// The slot used by `save` and `load` without a name
+pub const DEFAULT_SLOT: &str = "default";
+
+/// Details of a save, shown when listing the save slots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    /// The name of the slot
+    pub name: String,
+    /// When the game was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+    /// The name of the room the player was in
+    pub location: String,
+    /// The number of turns played
+    pub turns: u32,
+    /// The id of the world the game was played in
+    pub world_id: String,
+}
+
+impl fmt::Display for SaveMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} - {} in {}, turn {}, saved {}",
+            self.name,
+            self.world_id,
+            self.location,
+            self.turns,
+            format_timestamp(self.saved_at)
+        )
+    }
+}
+
+/// The contents of a save file
+#[derive(Debug, Serialize, Deserialize)]
+struct SaveFile {
+    /// The version of the save format, see `save_migrations`
+    version: u64,
+    /// The fingerprint of the world definition the game was played in, or
+    /// empty if it is not known
+    world_hash: String,
+    metadata: SaveMetadata,
+    state: WorldState,
+}
+
+/// The save slots in a directory
+#[derive(Debug, Clone)]
+pub struct SaveSlots {
+    dir: PathBuf,
+}
+
+impl Default for SaveSlots {
+    fn default() -> SaveSlots {
+        SaveSlots::new(DEFAULT_SAVE_DIR)
+    }
+}
+
+impl SaveSlots {
+    /// Use the save slots in a directory, which is created on the first save
+    pub fn new(dir: impl Into<PathBuf>) -> SaveSlots {
+        SaveSlots { dir: dir.into() }
+    }
+
+    /// Save the state of the game to a slot, replacing any earlier save
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the slot
+    /// * `world` - the state of the game
+    /// * `definition` - the world as it was loaded from its definition
+    ///
+    /// # Errors
+    /// The name is not a valid slot name, or the game could not be saved
+    #[logfn(Info)]
+    pub fn save(&self, name: &str, world: &World, definition: &World) -> GameResult<String> {
+        let path = self.slot_path(name)?;
+        let save = SaveFile {
+            version: CURRENT_VERSION,
+            world_hash: world.definition_hash.clone(),
+            metadata: SaveMetadata {
+                name: name.to_string(),
+                saved_at: now(),
+                location: world
+                    .player_room()
+                    .map_or(world.player_location.as_str(), |room| room.display_name())
+                    .to_string(),
+                turns: world.turns,
+                world_id: world.id.clone(),
+            },
+            state: WorldState::capture(world, definition),
+        };
+
+        let json = serde_json::to_string(&save).map_err(|err| {
+            error!("Error serializing game state {:?}", err);
+            GameError::Serialization("could not save game".to_string())
+        })?;
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&path, json))
+            .map_err(|err| {
+                error!("Error saving game {:?}", err);
+                GameError::Io("could not save game".to_string())
+            })?;
+        Ok(format!("game saved to {}", name))
+    }
+
+    /// Load the state of the game from a slot, upgrading saves made by older
+    /// versions of the game
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the slot
+    /// * `definition` - the world as it was loaded from its definition, which
+    ///   the save must belong to
+    ///
+    /// # Errors
+    /// The slot is empty or could not be read, or belongs to another world
+    #[logfn(Info)]
+    pub fn load(&self, name: &str, definition: &World) -> GameResult<World> {
+        let path = self.slot_path(name)?;
+        let contents = fs::read_to_string(&path).map_err(|err| {
+            info!("Error reading save {:?}", err);
+            GameError::Refused(format!("there is no save called {}", name))
+        })?;
+        let save = read_save(&contents, name)?;
+
+        let world_id = &save.metadata.world_id;
+        if !world_id.is_empty() && *world_id != definition.id {
+            return Err(GameError::Refused(format!(
+                "{} is a save for the world '{}', not '{}'",
+                name, world_id, definition.id
+            )));
+        }
+        if !save.world_hash.is_empty() && save.world_hash != definition.definition_hash {
+            warn!(
+                "{} was saved in a different version of the world '{}'",
+                name, definition.id
+            );
+        }
+
+        save.state
+            .apply(definition)
+            .map_err(|err| GameError::Refused(format!("could not load {}: {}", name, err)))
+    }
+
+    /// List the saves, most recent first. Files that are not saves are
+    /// skipped.
+    ///
+    /// # Errors
+    /// The save directory could not be read
+    pub fn list(&self) -> GameResult<Vec<SaveMetadata>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_err) if !self.dir.exists() => return Ok(Vec::new()),
+            Err(err) => {
+                error!("Error listing saves {:?}", err);
+                return Err(GameError::Io("could not list saves".to_string()));
+            }
+        };
+
+        let saves = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+                let contents = fs::read_to_string(&path).ok()?;
+                read_save(&contents, &name).ok()
+            })
+            .map(|save| save.metadata)
+            .sorted_by(|a, b| b.saved_at.cmp(&a.saved_at).then(a.name.cmp(&b.name)))
+            .collect();
+        Ok(saves)
+    }
+
+    /// Lists the saves for the player
+    ///
+    /// # Errors
+    /// The save directory could not be read
+    pub fn describe(&self) -> GameResult<String> {
+        let saves = self.list()?;
+        if saves.is_empty() {
+            Ok("There are no saved games".to_string())
+        } else {
+            Ok(saves.iter().join("\n"))
+        }
+    }
+
+    fn slot_path(&self, name: &str) -> GameResult<PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(self.dir.join(format!("{}.json", name)))
+        } else {
+            Err(GameError::Refused(format!(
+                "{} is not a valid save name, use letters, numbers, - and _",
+                name
+            )))
+        }
+    }
+}
+
+/// Parse a save file, upgrading it to the current format
+fn read_save(contents: &str, name: &str) -> GameResult<SaveFile> {
+    let value = serde_json::from_str(contents).map_err(|err| {
+        error!("Error deserializing game state {:?}", err);
+        GameError::Serialization("could not load game".to_string())
+    })?;
+    let value = save_migrations::migrate(value, name)?;
+    serde_json::from_value(value).map_err(|err| {
+        error!("Error deserializing game state {:?}", err);
+        GameError::Serialization("could not load game".to_string())
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Convert days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+#[path = "./saves_tests.rs"]
+mod saves_tests;
@@ -0,0 +1,124 @@
+use super::{format_timestamp, SaveSlots};
+use crate::world_building::World;
+use crate::DEFAULT_WORLD;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn empty_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("text_adventure_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_save_load_and_list_slots() {
+    // Arrange
+    let dir = empty_dir("slots");
+    let slots = SaveSlots::new(&dir);
+    let definition = World::from_file(DEFAULT_WORLD).unwrap();
+    let mut world = definition.clone();
+    world.turns = 3;
+
+    // Act
+    let first = slots.save("first", &world, &definition);
+    world.player_location = "corridor".to_string();
+    world.turns = 5;
+    let second = slots.save("second", &world, &definition);
+    let loaded = slots.load("first", &definition).unwrap();
+    let listed = slots.list().unwrap();
+
+    // Assert
+    assert_eq!(first.unwrap(), "game saved to first");
+    assert!(second.is_ok());
+    assert_eq!(loaded.player_location, "entrance");
+    assert_eq!(loaded.turns, 3);
+    assert_eq!(listed.len(), 2);
+    let second = listed.iter().find(|save| save.name == "second").unwrap();
+    assert_eq!(second.location, "Corridor");
+    assert_eq!(second.turns, 5);
+    assert_eq!(second.world_id, "dungeon");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_missing_and_invalid_slots() {
+    let slots = SaveSlots::new(empty_dir("missing"));
+
+    assert_eq!(slots.list().unwrap(), vec![]);
+    assert_eq!(
+        slots
+            .load("nothing", &World::default())
+            .unwrap_err()
+            .to_string(),
+        "there is no save called nothing"
+    );
+    assert!(slots
+        .save("../escape", &World::default(), &World::default())
+        .unwrap_err()
+        .to_string()
+        .contains("not a valid save name"));
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+    assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34 UTC");
+}
+
+#[test]
+fn test_load_migrates_bare_world_saves() {
+    // Arrange
+    let dir = empty_dir("legacy");
+    fs::create_dir_all(&dir).unwrap();
+    let world = World::from_file(DEFAULT_WORLD).unwrap();
+    let legacy = r#"{
+        "locations": { "corridor": { "id": "corridor", "description": "A corridor", "exits": { "south": "entrance" }, "items": [], "features": [] } },
+        "player_location": "corridor",
+        "player": { "name": "Bob", "inventory": [{ "name": "key" }] }
+    }"#;
+    fs::write(dir.join("old.json"), legacy).unwrap();
+    let slots = SaveSlots::new(&dir);
+
+    // Act
+    let loaded = slots.load("old", &world).unwrap();
+    let listed = slots.list().unwrap();
+
+    // Assert
+    assert_eq!(loaded.player_location, "corridor");
+    assert!(loaded.player.has_item("key"));
+    assert!(loaded.locations["corridor"].features.is_empty());
+    assert_eq!(loaded.id, "dungeon");
+    assert_eq!(listed[0].name, "old");
+    assert_eq!(listed[0].location, "corridor");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_load_refuses_other_worlds_and_newer_formats() {
+    // Arrange
+    let dir = empty_dir("other");
+    let slots = SaveSlots::new(&dir);
+    let world = World::from_file(DEFAULT_WORLD).unwrap();
+    let other = World {
+        id: "castle".to_string(),
+        ..World::default()
+    };
+    slots.save("castle", &other, &other).unwrap();
+    fs::write(dir.join("future.json"), r#"{ "version": 99 }"#).unwrap();
+
+    // Act
+    let other_world = slots.load("castle", &world);
+    let future = slots.load("future", &world);
+
+    // Assert
+    assert_eq!(
+        other_world.unwrap_err().to_string(),
+        "castle is a save for the world 'castle', not 'dungeon'"
+    );
+    assert_eq!(
+        future.unwrap_err().to_string(),
+        "future was saved by a newer version of the game (format 99)"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,266 @@
+#![warn(missing_docs)]
+
+//! The `vocabulary` module holds the words the player can use for each verb
+//! and direction. The parser only understands one word per verb, e.g.
+//! "take", so other words such as "grab" are rewritten to it first. Worlds
+//! can add their own words on top of the built in ones.
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The verbs the parser understands, which synonyms are given for
+pub const VERBS: [&str; 19] = [
+    "again",
+    "close",
+    "drop",
+    "examine",
+    "go",
+    "inventory",
+    "load",
+    "lock",
+    "look",
+    "open",
+    "put",
+    "quit",
+    "redo",
+    "save",
+    "saves",
+    "take",
+    "undo",
+    "unlock",
+    "use",
+];
+
+const DEFAULT_VERBS: [(&str, &[&str]); 11] = [
+    ("again", &["g"]),
+    ("close", &["shut"]),
+    ("drop", &["discard", "put down"]),
+    ("examine", &["x", "look at", "inspect", "check"]),
+    ("go", &["move", "walk", "run", "head"]),
+    ("inventory", &["inv", "i"]),
+    ("look", &["l"]),
+    ("put", &["place", "insert"]),
+    ("quit", &["exit", "q"]),
+    ("take", &["get", "grab", "pick up"]),
+    ("use", &["apply"]),
+];
+
+const DEFAULT_DIRECTIONS: [(&str, &[&str]); 10] = [
+    ("north", &["n", "northward", "northwards"]),
+    ("south", &["s", "southward", "southwards"]),
+    ("east", &["e", "eastward", "eastwards"]),
+    ("west", &["w", "westward", "westwards"]),
+    ("northeast", &["ne"]),
+    ("northwest", &["nw"]),
+    ("southeast", &["se"]),
+    ("southwest", &["sw"]),
+    ("up", &["u", "upward", "upwards", "upstairs"]),
+    ("down", &["d", "downward", "downwards", "downstairs"]),
+];
+
+/// The words for each verb and direction, stored by the verb or direction
+/// they stand for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vocabulary {
+    /// Other words for the verbs in `VERBS`, e.g. "take": ["grab"]
+    #[serde(default)]
+    pub verbs: BTreeMap<String, Vec<String>>,
+    /// Other words for directions, e.g. "north": ["n"]. Directions do not
+    /// need to be listed to be used.
+    #[serde(default)]
+    pub directions: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Vocabulary {
+    /// The built in words, for worlds that do not add their own
+    fn default() -> Vocabulary {
+        Vocabulary {
+            verbs: table(&DEFAULT_VERBS),
+            directions: table(&DEFAULT_DIRECTIONS),
+        }
+    }
+}
+
+impl Vocabulary {
+    /// Adds the words of another vocabulary to this one
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the words to add, such as those from a world definition
+    pub fn extend(&mut self, other: Vocabulary) {
+        for (table, additions) in [
+            (&mut self.verbs, other.verbs),
+            (&mut self.directions, other.directions),
+        ] {
+            for (key, words) in additions {
+                let entry = table.entry(key.to_lowercase()).or_default();
+                for word in words {
+                    let word = word.to_lowercase();
+                    if !entry.contains(&word) {
+                        entry.push(word);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a direction the player can move in, so that it is recognized
+    /// without a verb
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - the direction of an exit, e.g. "portal"
+    pub fn add_direction(&mut self, direction: &str) {
+        self.directions.entry(direction.to_lowercase()).or_default();
+    }
+
+    /// Adds a verb the player can use, such as one a rule of the world
+    /// responds to
+    ///
+    /// # Arguments
+    ///
+    /// * `verb` - the verb, e.g. "light"
+    pub fn add_verb(&mut self, verb: &str) {
+        self.verbs.entry(verb.to_lowercase()).or_default();
+    }
+
+    /// Returns true if the word is a built in verb or one the world added
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - the word to look up
+    pub fn is_verb(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        VERBS.contains(&word.as_str()) || self.verbs.contains_key(&word)
+    }
+
+    /// Rewrites the verb the input starts with as the word the parser
+    /// understands, e.g. "pick up key" becomes "take key". The longest
+    /// matching phrase wins, so "look at" is preferred over "look".
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - the player's command
+    pub fn normalize_verb(&self, input: &str) -> String {
+        let words = input.split_whitespace().collect_vec();
+
+        let verb = self
+            .verbs
+            .iter()
+            .flat_map(|(verb, synonyms)| {
+                synonyms
+                    .iter()
+                    .map(move |synonym| (verb, synonym.split_whitespace().count(), synonym))
+            })
+            .filter(|(_verb, length, synonym)| starts_with_phrase(&words, synonym, *length))
+            .max_by_key(|(_verb, length, _synonym)| *length);
+
+        match verb {
+            Some((verb, length, _synonym)) => std::iter::once(verb.as_str())
+                .chain(words[length..].iter().copied())
+                .join(" "),
+            None => words.join(" "),
+        }
+    }
+
+    /// Gets the direction a word stands for, e.g. "north" for "n", if the
+    /// word is a known direction or one of its aliases
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - the word to look up
+    pub fn direction(&self, word: &str) -> Option<String> {
+        let word = word.trim().to_lowercase();
+        self.directions
+            .iter()
+            .find(|(direction, aliases)| **direction == word || aliases.contains(&word))
+            .map(|(direction, _aliases)| direction.clone())
+    }
+
+    /// Suggests the verb or direction the player may have meant by a word
+    /// that is not in the vocabulary, e.g. "take" for "tkae"
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - the word that was not understood
+    pub fn suggest(&self, word: &str) -> Option<String> {
+        let verbs = VERBS.iter().copied();
+        let synonyms = self.verbs.values().flatten().map(String::as_str);
+        let directions = self.directions.iter().flat_map(|(direction, aliases)| {
+            std::iter::once(direction.as_str()).chain(aliases.iter().map(String::as_str))
+        });
+        closest(word, verbs.chain(synonyms).chain(directions))
+    }
+}
+
+/// Finds the candidate closest to a misspelled word, allowing one mistake in
+/// short words and two in longer ones. Candidates shorter than three letters
+/// are too easily reached to be useful suggestions.
+///
+/// # Arguments
+///
+/// * `word` - the word that was not understood
+/// * `candidates` - the words the player may have meant
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let word = word.to_lowercase();
+    let allowed = if word.chars().count() <= 4 { 1 } else { 2 };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.chars().count() >= 3 && *candidate != word)
+        .map(|candidate| (edit_distance(&word, candidate), candidate))
+        .filter(|(distance, _candidate)| *distance <= allowed)
+        .min()
+        .map(|(_distance, candidate)| candidate.to_string())
+}
+
+/// The number of letters inserted, deleted, changed or swapped with their
+/// neighbour to turn one word into the other
+fn edit_distance(from: &str, to: &str) -> usize {
+    let from = from.chars().collect_vec();
+    let to = to.chars().collect_vec();
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=to.len()).collect();
+
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let cost = usize::from(from[i - 1] != to[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && from[i - 1] == to[j - 2] && from[i - 2] == to[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[from.len()][to.len()]
+}
+
+fn table(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+    entries
+        .iter()
+        .map(|(key, words)| {
+            (
+                key.to_string(),
+                words.iter().map(|word| word.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+fn starts_with_phrase(words: &[&str], phrase: &str, length: usize) -> bool {
+    length > 0
+        && words.len() >= length
+        && words
+            .iter()
+            .zip(phrase.split_whitespace())
+            .all(|(word, phrase_word)| word.eq_ignore_ascii_case(phrase_word))
+}
+
+#[cfg(test)]
+#[path = "./vocabulary_tests.rs"]
+mod vocabulary_tests;
@@ -0,0 +1,50 @@
+use super::{closest, Vocabulary};
+
+#[test]
+fn test_normalize_verb_prefers_longest_phrase() {
+    let vocabulary = Vocabulary::default();
+
+    assert_eq!(
+        vocabulary.normalize_verb("look at  the door"),
+        "examine the door"
+    );
+    assert_eq!(vocabulary.normalize_verb("LOOK"), "LOOK");
+    assert_eq!(
+        vocabulary.normalize_verb("Grab phat loot"),
+        "take phat loot"
+    );
+    assert_eq!(vocabulary.normalize_verb("dance"), "dance");
+}
+
+#[test]
+fn test_extend_adds_words_from_the_world() {
+    // Arrange
+    let mut vocabulary = Vocabulary::default();
+    let world_words = Vocabulary {
+        verbs: vec![("take".to_string(), vec!["Snatch".to_string()])]
+            .into_iter()
+            .collect(),
+        directions: vec![("portal".to_string(), vec!["through".to_string()])]
+            .into_iter()
+            .collect(),
+    };
+
+    // Act
+    vocabulary.extend(world_words);
+
+    // Assert
+    assert_eq!(vocabulary.normalize_verb("snatch key"), "take key");
+    assert_eq!(vocabulary.direction("through"), Some("portal".to_string()));
+    assert_eq!(vocabulary.direction("N"), Some("north".to_string()));
+    assert_eq!(vocabulary.direction("sideways"), None);
+}
+
+#[test]
+fn test_closest_allows_few_mistakes() {
+    let candidates = ["lantern", "take", "tinderbox", "n"];
+
+    assert_eq!(closest("lantren", candidates), Some("lantern".to_string()));
+    assert_eq!(closest("TAKR", candidates), Some("take".to_string()));
+    assert_eq!(closest("tkr", candidates), None);
+    assert_eq!(closest("m", candidates), None);
+}
@@ -7,19 +7,220 @@ use log_derive::{logfn, logfn_inputs};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 
-use crate::entities::{interactive, Player, Room};
+use crate::entities::interactive::{self, Effect, Rule};
+use crate::entities::{Container, Feature, Item, Player, Room};
+use crate::errors::{GameError, GameResult};
+use crate::flags::{self, Flags};
+use crate::vocabulary::Vocabulary;
+use crate::world_validation::ValidationError;
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+/// The current state of the game world
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct World {
+    /// Identifies the world a saved game belongs to
+    #[serde(default)]
+    pub id: String,
+    /// A fingerprint of the world definition the world was built from
+    #[serde(skip)]
+    pub definition_hash: String,
     /// The rooms making up the world, stored by Room.id
     pub locations: HashMap<String, Room>,
     /// The id of the current player's location
     pub player_location: String,
+    /// The player exploring the world
     pub player: Player,
+    /// What happens when the player uses items on features
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    /// The variables remembering what has happened in the world
+    #[serde(default)]
+    pub flags: Flags,
+    /// The number of turns the player has taken
+    #[serde(default)]
+    pub turns: u32,
+    /// The words the player can use for verbs and directions
+    #[serde(default)]
+    pub vocabulary: Vocabulary,
+}
+
+/// The format content authors write worlds in, see `worlds/dungeon.json`.
+///
+/// Rooms are kept as raw JSON until they are converted one at a time, so
+/// that errors can name the room they came from.
+#[derive(Debug, Deserialize)]
+struct WorldDefinition {
+    /// Identifies the world, defaulting to the name of its file
+    #[serde(default)]
+    id: String,
+    /// The id of the room the player starts in
+    start: String,
+    #[serde(default)]
+    player: Player,
+    rooms: Vec<serde_json::Value>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    #[serde(default)]
+    flags: Flags,
+    /// Words for verbs and directions, added to the built in ones
+    #[serde(default)]
+    vocabulary: Option<Vocabulary>,
 }
 
 impl World {
+    /// Load a world definition from a JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the world definition file
+    ///
+    /// # Errors
+    /// The file could not be read or does not describe a valid world
+    #[logfn(Info)]
+    pub fn from_file(path: &str) -> GameResult<World> {
+        let contents = read_definition(path)?;
+        let mut world = World::from_json(&contents)
+            .map_err(|err| GameError::InvalidWorld(format!("{}: {}", path, err)))?;
+        if world.id.is_empty() {
+            world.id = Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(world)
+    }
+
+    /// Build a world from the JSON text of a world definition. Problems that
+    /// still leave the world playable are logged as warnings.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - the world definition
+    ///
+    /// # Errors
+    /// The definition is malformed or fails validation
+    pub fn from_json(json: &str) -> GameResult<World> {
+        let (mut world, problems) = World::parse_definition(json)?;
+        world.definition_hash = fingerprint(json);
+
+        let mut errors = Vec::new();
+        for problem in problems {
+            if problem.is_warning() {
+                warn!("{}", problem);
+            } else {
+                errors.push(problem.to_string());
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(world)
+        } else {
+            Err(GameError::InvalidWorld(errors.join("\n")))
+        }
+    }
+
+    /// Check a world definition file, returning every problem found
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the world definition file
+    ///
+    /// # Errors
+    /// The file could not be read or is not a world definition at all
+    pub fn check_file(path: &str) -> GameResult<Vec<ValidationError>> {
+        let contents = read_definition(path)?;
+        World::check_json(&contents)
+            .map_err(|err| GameError::InvalidWorld(format!("{}: {}", path, err)))
+    }
+
+    /// Check the JSON text of a world definition, returning every problem
+    /// found
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - the world definition
+    ///
+    /// # Errors
+    /// The text is not a world definition at all
+    pub fn check_json(json: &str) -> GameResult<Vec<ValidationError>> {
+        World::parse_definition(json).map(|(_world, problems)| problems)
+    }
+
+    /// Convert a world definition into a `World` along with the problems
+    /// found in it. Only the first of several rooms sharing an id is kept.
+    fn parse_definition(json: &str) -> GameResult<(World, Vec<ValidationError>)> {
+        let definition: WorldDefinition = serde_json::from_str(json)
+            .map_err(|err| GameError::InvalidWorld(format!("invalid world: {}", err)))?;
+
+        let mut world = World {
+            id: definition.id,
+            player_location: definition.start,
+            player: definition.player,
+            rules: definition.rules,
+            flags: definition.flags,
+            ..World::default()
+        };
+        if let Some(vocabulary) = definition.vocabulary {
+            world.vocabulary.extend(vocabulary);
+        }
+        let mut problems = Vec::new();
+
+        for (index, value) in definition.rooms.into_iter().enumerate() {
+            let label = match value.get("id").and_then(|id| id.as_str()) {
+                Some(id) => format!("room '{}'", id),
+                None => format!("room #{}", index + 1),
+            };
+            let mut room: Room = serde_json::from_value(value)
+                .map_err(|err| GameError::InvalidWorld(format!("{}: {}", label, err)))?;
+            room.assign_default_ids();
+            if world.locations.contains_key(&room.id) {
+                problems.push(ValidationError::DuplicateRoom { room: room.id });
+            } else {
+                world.add_location(room);
+            }
+        }
+
+        for item in &mut world.player.inventory {
+            item.assign_default_ids();
+        }
+        for rule in &mut world.rules {
+            rule.assign_default_ids();
+        }
+        world.learn_words();
+
+        problems.extend(world.validate());
+        Ok((world, problems))
+    }
+
+    /// Adds the verbs the rules respond to to the vocabulary, so the parser
+    /// accepts them, along with the directions of every exit in the world,
+    /// including those rules open, so the player can use them on their own
+    fn learn_words(&mut self) {
+        for verb in self.rules.iter().flat_map(|rule| &rule.verbs) {
+            self.vocabulary.add_verb(verb);
+        }
+
+        let rule_directions =
+            self.rules
+                .iter()
+                .flat_map(|rule| &rule.effects)
+                .filter_map(|effect| match effect {
+                    Effect::OpenExit { direction, .. } => Some(direction),
+                    _ => None,
+                });
+        let directions = self
+            .locations
+            .values()
+            .flat_map(|room| room.get_exits())
+            .chain(rule_directions)
+            .cloned()
+            .collect::<Vec<String>>();
+        for direction in directions {
+            self.vocabulary.add_direction(&direction);
+        }
+    }
+
     /// Adds a location to the world
     pub fn add_location(&mut self, location: Room) {
         self.locations.insert(location.id.clone(), location);
@@ -30,172 +231,525 @@ impl World {
     /// # Arguments
     ///
     /// * `direction` - A name that corresponds to the name of an exit from the
-    /// players current location
+    ///   players current location
     ///
     /// # Errors
-    /// The location is invalid
+    /// There is no such exit, or it is locked or its conditions do not hold
     #[logfn(Info)]
     #[logfn_inputs(Info)]
-    pub fn move_player(&mut self, direction: &String) -> Result<String, String> {
-        // Check whether our current location has an exit that matches direction.
-        // If so, set the payers location to the pointed direction.
-        // returns a result with Ok(new_location), Err(No Exit)
+    pub fn move_player(&mut self, direction: &str) -> GameResult<String> {
+        let exit = self
+            .current_room()?
+            .exits
+            .get(direction)
+            .ok_or_else(|| GameError::UnknownDirection(direction.to_string()))?;
 
-        let new_room = self
-            .locations
-            .get(&self.player_location)
-            .and_then(|cl| cl.exits.get(direction));
+        exit.check_passable(direction, self)?;
+        self.player_location = exit.destination.clone();
+        Ok(format!("You have moved {}", direction))
+    }
+
+    /// Gets the room the player is currently in
+    pub fn player_room(&self) -> Option<&Room> {
+        self.locations.get(&self.player_location)
+    }
+
+    /// Gets the room the player is currently in, which must exist
+    ///
+    /// # Errors
+    /// The player's location does not exist
+    fn current_room(&self) -> GameResult<&Room> {
+        self.player_room()
+            .ok_or_else(|| GameError::MissingRoom(self.player_location.clone()))
+    }
+
+    /// Gets a mutable reference to the room the player is currently in,
+    /// which must exist
+    ///
+    /// # Errors
+    /// The player's location does not exist
+    pub fn current_room_mut(&mut self) -> GameResult<&mut Room> {
+        room_mut(&mut self.locations, &self.player_location)
+    }
 
-        match new_room {
-            Some(room_id) => {
-                self.player_location = room_id.clone();
-                Ok(format!("You have moved {}", direction))
+    /// Creates the full description of the room the player is in
+    ///
+    /// # Errors
+    /// The player's location does not exist
+    pub fn describe_player_room(&self) -> GameResult<String> {
+        self.current_room()
+            .map(|room| room.get_full_description(self))
+    }
+
+    /// Take the specified item from the players current location, or from
+    /// a container the player can reach and see into
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id` - the id of the item to take
+    ///
+    /// # Errors
+    /// The item is not in the current location
+    pub fn take_item(&mut self, item_id: &str) -> GameResult<String> {
+        let holder = self
+            .reachable_containers()?
+            .into_iter()
+            .find(|(_id, _name, container)| {
+                container.shows_contents()
+                    && container
+                        .contents
+                        .iter()
+                        .any(|i| i.id == item_id && !i.properties.hidden)
+            })
+            .map(|(id, _name, _container)| id.to_string());
+        if let Some(holder) = holder {
+            return self.take_from(item_id, &holder);
+        }
+
+        let room = room_mut(&mut self.locations, &self.player_location)?;
+        room.take_item(&mut self.player, item_id)
+    }
+
+    /// Take an item out of a container the player can reach
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id` - the id of the item to take
+    /// * `container_id` - the id of the container holding it
+    ///
+    /// # Errors
+    /// The container is missing, closed or not a container, does not hold
+    /// the item, or the item is too much for the player to carry
+    pub fn take_from(&mut self, item_id: &str, container_id: &str) -> GameResult<String> {
+        let carried = holds(&self.player.inventory, container_id);
+        let item = self
+            .reachable_containers()?
+            .into_iter()
+            .find(|(id, _name, container)| *id == container_id && container.open)
+            .and_then(|(_id, _name, container)| {
+                container
+                    .contents
+                    .iter()
+                    .find(|i| i.id == item_id && !i.properties.hidden)
+            });
+        if let Some(item) = item {
+            if !item.properties.is_portable() {
+                return Err(item.properties.refuse_take(&item.name));
             }
-            None => Err(format!("{} is not a valid direction", direction)),
+            self.player.can_carry(item, carried)?;
         }
+
+        let (name, container) = self.find_container(container_id, "take things from")?;
+        let item = container.remove(&name, item_id)?;
+        let message = format!("You take the {} from the {}", item.name, name);
+        self.player.inventory.push(item);
+        Ok(message)
     }
 
-    /// Gets a mutable reference to the room the player is currently in
-    pub fn get_player_room(&mut self) -> Option<&mut Room> {
-        self.locations.get_mut(&self.player_location)
+    /// Open a container the player can reach
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the container
+    ///
+    /// # Errors
+    /// The container is missing, cannot be opened, or is locked or open
+    pub fn open(&mut self, id: &str) -> GameResult<String> {
+        let (name, container) = self.find_container(id, "open")?;
+        container.open(&name)
     }
 
-    /// Save the state of the game to a local file
+    /// Close a container the player can reach
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the container
     ///
     /// # Errors
-    /// Could not save the game
-    #[logfn(Info)]
-    pub fn save_state(&self) -> Result<String, String> {
-        match serde_json::to_string(self) {
-            Ok(json) => match fs::write("savedata.json", json) {
-                Ok(_msg) => Ok("game saved".to_string()),
-                Err(err) => {
-                    error!("Error saving game {:?}", err);
-                    Err("could not save game".to_string())
-                }
-            },
-            Err(err) => {
-                error!("Error serializing game state {:?}", err);
-                Err("could not save game".to_string())
+    /// The container is missing, cannot be closed, or is already closed
+    pub fn close(&mut self, id: &str) -> GameResult<String> {
+        let (name, container) = self.find_container(id, "close")?;
+        container.close(&name)
+    }
+
+    /// Describe what is inside a container the player can reach
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the container
+    ///
+    /// # Errors
+    /// The container is missing, not a container, or closed and opaque
+    pub fn look_in(&mut self, id: &str) -> GameResult<String> {
+        let (name, container) = self.find_container(id, "look inside")?;
+        match container.visible_contents() {
+            Some(contents) => Ok(format!("The {} contains {}", name, contents)),
+            None if container.shows_contents() => Ok(format!("The {} is empty", name)),
+            None => Err(GameError::Refused(format!("The {} is closed", name))),
+        }
+    }
+
+    /// Describe a feature or item in the current location or the player's
+    /// inventory
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the thing to examine
+    ///
+    /// # Errors
+    /// Nothing with that id is present
+    pub fn examine(&self, id: &str) -> GameResult<String> {
+        let room = self.current_room()?;
+
+        if let Some(feature) = room
+            .features
+            .iter()
+            .find(|f| f.id == id && !f.properties.hidden)
+        {
+            return Ok(feature.get_description(self));
+        }
+
+        self.visible_items()?
+            .into_iter()
+            .find(|i| i.id == id)
+            .map(|item| item.get_description(self))
+            .ok_or_else(|| GameError::MissingItem(id.to_string()))
+    }
+
+    /// The items the player can see: those in the room and the inventory,
+    /// along with anything inside open or transparent containers among them
+    ///
+    /// # Errors
+    /// The player's location does not exist
+    pub fn visible_items(&self) -> GameResult<Vec<&Item>> {
+        let room = self.current_room()?;
+        let mut visible = Vec::new();
+        for container in room
+            .features
+            .iter()
+            .filter(|f| !f.properties.hidden)
+            .filter_map(|f| f.container.as_ref())
+        {
+            collect_visible(container, &mut visible);
+        }
+        for item in room
+            .items
+            .iter()
+            .filter(|i| !i.properties.hidden)
+            .chain(&self.player.inventory)
+        {
+            visible.push(item);
+            if let Some(container) = &item.container {
+                collect_visible(container, &mut visible);
             }
         }
+        Ok(visible)
     }
 
-    /// Load the state of the game from a local file
+    /// The containers the player can reach, as id, name and container:
+    /// those among the room's features and items and the inventory, along
+    /// with any inside open containers
     ///
     /// # Errors
-    /// Could not load the game
-    #[logfn(Info)]
-    pub fn load_state(&mut self) -> Result<String, String> {
-        match fs::read_to_string("savedata.json") {
-            Ok(contents) => match serde_json::from_str::<World>(&contents) {
-                Ok(new_world) => {
-                    self.locations = new_world.locations;
-                    self.player = new_world.player;
-                    self.player_location = new_world.player_location;
-                    Ok("game loaded".to_string())
-                }
-                Err(err) => {
-                    error!("Error loading game {:?}", err);
-                    Err("could not load game".to_string())
-                }
-            },
+    /// The player's location does not exist
+    fn reachable_containers(&self) -> GameResult<Vec<(&str, &str, &Container)>> {
+        let room = self.current_room()?;
+        let mut reachable = Vec::new();
+        for feature in room.features.iter().filter(|f| !f.properties.hidden) {
+            if let Some(container) = &feature.container {
+                reachable.push((feature.id.as_str(), feature.name.as_str(), container));
+                collect_reachable(container, &mut reachable);
+            }
+        }
+        for item in room
+            .items
+            .iter()
+            .filter(|i| !i.properties.hidden)
+            .chain(&self.player.inventory)
+        {
+            if let Some(container) = &item.container {
+                reachable.push((item.id.as_str(), item.name.as_str(), container));
+                collect_reachable(container, &mut reachable);
+            }
+        }
+        Ok(reachable)
+    }
+
+    /// Drop an item from the player's inventory in the current location
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id` - the id of the item to drop
+    ///
+    /// # Errors
+    /// The player is not carrying the item
+    pub fn drop_item(&mut self, item_id: &str) -> GameResult<String> {
+        let index = self
+            .inventory_position(item_id)
+            .ok_or_else(|| GameError::NotCarrying(item_id.to_string()))?;
+
+        let room = room_mut(&mut self.locations, &self.player_location)?;
+        let item = self.player.inventory.remove(index);
+        let message = format!("Dropped {}", item.name);
+        room.add_item(item);
+        Ok(message)
+    }
+
+    /// Put an item from the player's inventory into a container, which may be
+    /// a feature or item of the current location or another carried item
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id` - the id of the item to put away
+    /// * `target` - the id of the container
+    ///
+    /// # Errors
+    /// The player is not carrying the item, or the target is missing or is not
+    /// a container
+    pub fn put_item(&mut self, item_id: &str, target: &str) -> GameResult<String> {
+        let index = self
+            .inventory_position(item_id)
+            .ok_or_else(|| GameError::NotCarrying(item_id.to_string()))?;
+        if item_id == target {
+            return Err(GameError::Refused(format!(
+                "You cannot put the {} inside itself",
+                self.player.inventory[index].name
+            )));
+        }
+
+        let item = self.player.inventory.remove(index);
+        let item_name = item.name.clone();
+        let container = self
+            .find_container(target, "put things in")
+            .and_then(|(name, container)| container.accepts(&name).map(|()| (name, container)));
+        match container {
+            Ok((container_name, container)) => {
+                container.add_item(item);
+                Ok(format!(
+                    "You put the {} in the {}",
+                    item_name, container_name
+                ))
+            }
             Err(err) => {
-                error!("Error deserializing game state {:?}", err);
-                Err(format!("{:?}", err))
+                self.player.inventory.insert(index, item);
+                Err(err)
             }
         }
     }
 
-    /// Take the specified item from the players current location
+    fn inventory_position(&self, item_id: &str) -> Option<usize> {
+        self.player.inventory.iter().position(|i| i.id == item_id)
+    }
+
+    /// Finds a container the player can reach by id, along with the name of
+    /// the thing holding it
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the item or feature holding the container
+    /// * `verb` - what the player is trying to do, for the refusal when it
+    ///   is not a container, e.g. "open"
+    ///
+    /// # Errors
+    /// Nothing with that id is present, or it is not a container
+    fn find_container(&mut self, id: &str, verb: &str) -> GameResult<(String, &mut Container)> {
+        let room = room_mut(&mut self.locations, &self.player_location)?;
+
+        let holder = match find_feature_holder(&mut room.features, id) {
+            Some(holder) => Some(holder),
+            None => match find_item_holder(&mut room.items, id) {
+                Some(holder) => Some(holder),
+                None => find_item_holder(&mut self.player.inventory, id),
+            },
+        };
+
+        match holder {
+            Some((name, Some(container))) => Ok((name, container)),
+            Some((name, None)) => Err(GameError::Refused(format!(
+                "You cannot {} the {}",
+                verb, name
+            ))),
+            None => Err(GameError::MissingItem(id.to_string())),
+        }
+    }
+
+    /// Use an item from the player's inventory on a feature of the current
+    /// location, triggering the first rule for the verb that covers the
+    /// combination and whose conditions hold. Without a rule, using or
+    /// unlocking with a key unlocks the exit it belongs to. Containers with
+    /// a key are locked and unlocked before any rule is looked for.
     ///
     /// # Arguments
     ///
-    /// * `item_name` - the name of the item to take
-    pub fn take_item(&mut self, item_name: &str) -> Result<String, String> {
-        match self.get_player_room() {
-            Some(room) => {
-                match room
-                    .items
-                    .iter()
-                    .position(|i| i.name.to_lowercase() == item_name.to_lowercase())
-                {
-                    Some(index) => {
-                        let item = room.items.remove(index);
-                        self.player.inventory.push(item);
-                        return Ok(format!("Picked up {}", item_name));
-                    }
-                    None => Err(format!("No item of type {} is present", item_name)),
+    /// * `verb` - how the item is used, e.g. "use" or "unlock"
+    /// * `subject` - the id of the item to use
+    /// * `target` - the id of the feature to use it on
+    ///
+    /// # Errors
+    /// The item or feature is not present, or no rule covers using them together
+    pub fn use_item(&mut self, verb: &str, subject: &str, target: &str) -> GameResult<String> {
+        if !self.player.has_item(subject) {
+            return Err(GameError::NotCarrying(subject.to_string()));
+        }
+        if matches!(verb, "use" | "unlock" | "lock") {
+            let key_name = self.player.item_name(subject);
+            if let Ok((name, container)) = self.find_container(target, verb) {
+                if container.key.is_some() {
+                    return container.set_locked(&name, subject, &key_name, verb == "lock");
                 }
             }
-            None => Err("Room does not exist".to_string()),
+        }
+        if !self.current_room()?.has_feature(target) {
+            return Err(GameError::MissingItem(target.to_string()));
+        }
+
+        match self.rules.iter().find(|rule| {
+            rule.matches(verb, subject, target, &self.player_location)
+                && flags::all_hold(&rule.conditions, self)
+        }) {
+            Some(rule) => {
+                let rule = rule.clone();
+                Ok(interactive::apply_rule(&rule, self))
+            }
+            None if verb == "use" || verb == "unlock" => self.unlock_exit(subject, target),
+            None => Err(GameError::Refused("You cannot do that here".to_string())),
         }
     }
 
-    pub fn use_item(&mut self, subject: &str, target: &str) -> Result<String, String> {
-        match self.get_player_room() {
-            Some(room) => {
-                // ####### THIS ISNT FINISHED YET!!!!
-                if room.has_feature(target) && self.player.has_item(subject) {
-                    let mut feature = &room
-                        .features
-                        .iter()
-                        .find(|feature| feature.name == target)
-                        .expect("Failed to locate feature in room!");
-                    let item = &self
-                        .player
-                        .inventory
-                        .iter()
-                        .find(|item| item.name == subject)
-                        .expect("Failed to locate item in inventory");
-                    interactive::use_item(item, &mut feature, room);
-                    Ok("Done".to_owned())
-                } else {
-                    Err("You cannot do that here".to_string())
-                }
+    /// Unlock the exit of the current location that the item is the key for
+    ///
+    /// # Errors
+    /// The item is not the key for any exit used on that target
+    fn unlock_exit(&mut self, subject: &str, target: &str) -> GameResult<String> {
+        let subject_name = self.player.item_name(subject);
+        let room = room_mut(&mut self.locations, &self.player_location)?;
+        let target_name = room.feature_name(target);
+        let exit = room
+            .exits
+            .iter_mut()
+            .find(|(direction, exit)| exit.is_unlocked_by(direction, subject, target));
+
+        match exit {
+            Some((_direction, exit)) if exit.locked => {
+                exit.locked = false;
+                Ok(format!(
+                    "You unlock the {} with the {}",
+                    target_name, subject_name
+                ))
             }
-            None => Err("Can't find the room the player is in.".to_string()),
+            Some(_) => Err(GameError::Refused(format!(
+                "The {} is already unlocked",
+                target_name
+            ))),
+            None => Err(GameError::Refused("You cannot do that here".to_string())),
         }
     }
 }
 
-/// Create a `World`
-macro_rules! shaper_of_worlds {
-    (
-        location = $player_location:expr,
-        rooms = [
-            $([
-                $room_name:expr,
-                $room_description:expr,
-                items=[$($item:expr$(,)*)*],
-                features=[$($feature:expr$(,)*)*],
-                exits=[$($dir:expr => $dest:expr)*]
-            ])+
-        ]
-    ) => {
-        {
-            let mut world = World::default();
-            world.player_location = $player_location.to_lowercase().to_string();
-            $(
-                let mut room = crate::entities::Room::new($room_name.to_lowercase().to_string(), $room_description.to_string());
-                $(
-                    let item = crate::entities::Item::new($item.to_string());
-                    room.add_item(item);
-                )*
-                $(
-                    let feature = crate::entities::Feature::new($feature.to_string());
-                    room.add_feature(feature);
-                )*
-                $(
-                    room.add_exit($dir.to_string(), $dest.to_lowercase().to_string());
-                )*
-                world.add_location(room);
-            )+
-            world
+/// Finds an item by id among the features' open containers, or a feature by
+/// id, returning its name and container
+fn find_feature_holder<'a>(
+    features: &'a mut [Feature],
+    id: &str,
+) -> Option<(String, &'a mut Option<Container>)> {
+    features.iter_mut().find_map(|feature| {
+        if feature.properties.hidden {
+            return None;
+        }
+        if feature.id == id {
+            return Some((feature.name.clone(), &mut feature.container));
+        }
+        feature
+            .container
+            .as_mut()
+            .filter(|container| container.open)
+            .and_then(|container| find_item_holder(&mut container.contents, id))
+    })
+}
+
+/// Finds an item by id among the items or inside their open containers,
+/// returning its name and container
+fn find_item_holder<'a>(
+    items: &'a mut [Item],
+    id: &str,
+) -> Option<(String, &'a mut Option<Container>)> {
+    items.iter_mut().find_map(|item| {
+        if item.properties.hidden {
+            return None;
+        }
+        if item.id == id {
+            return Some((item.name.clone(), &mut item.container));
+        }
+        item.container
+            .as_mut()
+            .filter(|container| container.open)
+            .and_then(|container| find_item_holder(&mut container.contents, id))
+    })
+}
+
+/// Checks if an item is among the items or anywhere inside them
+fn holds(items: &[Item], id: &str) -> bool {
+    items.iter().any(|item| {
+        item.id == id
+            || item
+                .container
+                .as_ref()
+                .is_some_and(|container| holds(&container.contents, id))
+    })
+}
+
+/// Adds the items that can be seen inside a container, and inside those
+fn collect_visible<'a>(container: &'a Container, visible: &mut Vec<&'a Item>) {
+    if !container.shows_contents() {
+        return;
+    }
+    for item in container.contents.iter().filter(|i| !i.properties.hidden) {
+        visible.push(item);
+        if let Some(inner) = &item.container {
+            collect_visible(inner, visible);
         }
-    };
+    }
+}
+
+/// Adds the containers inside an open container, and inside those
+fn collect_reachable<'a>(
+    container: &'a Container,
+    reachable: &mut Vec<(&'a str, &'a str, &'a Container)>,
+) {
+    if !container.open {
+        return;
+    }
+    for item in container.contents.iter().filter(|i| !i.properties.hidden) {
+        if let Some(inner) = &item.container {
+            reachable.push((item.id.as_str(), item.name.as_str(), inner));
+            collect_reachable(inner, reachable);
+        }
+    }
+}
+
+/// Gets a room by id, borrowing only the rooms so the rest of the world can
+/// be changed alongside it
+fn room_mut<'a>(locations: &'a mut HashMap<String, Room>, id: &str) -> GameResult<&'a mut Room> {
+    locations
+        .get_mut(id)
+        .ok_or_else(|| GameError::MissingRoom(id.to_string()))
+}
+
+/// A 64 bit FNV-1a hash of the text, which unlike the standard library's
+/// hashers is the same in every build
+fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn read_definition(path: &str) -> GameResult<String> {
+    fs::read_to_string(path)
+        .map_err(|err| GameError::Io(format!("could not read {}: {}", path, err)))
 }
 
 #[cfg(test)]
 #[path = "./world_building_tests.rs"]
+#[allow(clippy::bool_assert_comparison)]
 mod world_building_tests;
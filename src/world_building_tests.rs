@@ -1,4 +1,5 @@
 use super::Room;
+use super::World;
 
 #[test]
 fn test_add_exit() {
@@ -11,3 +12,491 @@ fn test_add_exit() {
     // Assert
     assert_eq!(result, true);
 }
+
+#[test]
+fn test_from_json() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice" },
+        "rooms": [
+            { "id": "hall", "description": "A hall", "items": [{ "name": "lamp" }],
+              "exits": { "east": "kitchen" } },
+            { "id": "kitchen", "description": "A kitchen", "exits": { "west": "hall" } }
+        ]
+    }"#;
+
+    // Act
+    let world = World::from_json(json).unwrap();
+
+    // Assert
+    assert_eq!(world.player_location, "hall");
+    assert_eq!(world.player.name, "Alice");
+    assert_eq!(world.locations.len(), 2);
+    assert!(world.locations["hall"].has_exit("east"));
+    assert_eq!(world.locations["hall"].items[0].name, "lamp");
+}
+
+#[test]
+fn test_from_json_reports_offending_room_and_exit() {
+    // Arrange
+    let json = r#"{
+        "start": "cellar",
+        "rooms": [
+            { "id": "hall", "description": "A hall", "exits": { "east": "kitchen" } }
+        ]
+    }"#;
+
+    // Act
+    let result = World::from_json(json);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "starting location 'cellar' is not a room\n\
+         room 'hall': exit 'east' leads to unknown room 'kitchen'"
+    );
+}
+
+#[test]
+fn test_from_json_names_malformed_room() {
+    // Arrange
+    let json = r#"{ "start": "hall", "rooms": [ { "id": "hall" } ] }"#;
+
+    // Act
+    let result = World::from_json(json);
+
+    // Assert
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("room 'hall': missing field `description`"));
+}
+
+#[test]
+fn test_use_item_applies_matching_rule() {
+    // Arrange
+    let json = r#"{
+        "start": "cell",
+        "player": { "name": "Alice", "inventory": [{ "name": "chisel" }] },
+        "rooms": [
+            { "id": "cell", "description": "A cell", "features": [{ "name": "cracked wall" }] },
+            { "id": "tunnel", "description": "A tunnel" }
+        ],
+        "rules": [
+            {
+                "item": "chisel",
+                "feature": "cracked wall",
+                "room": "cell",
+                "message": "The wall crumbles",
+                "effects": [
+                    { "replace_feature": { "feature": "cracked wall", "with": { "name": "rubble" } } },
+                    { "open_exit": { "direction": "east", "destination": "tunnel" } },
+                    { "spawn_item": { "name": "brick" } },
+                    "consume_item",
+                    { "print": "The chisel breaks" },
+                    { "set_flag": "wall broken" }
+                ]
+            }
+        ]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let result = world.use_item("use", "chisel", "cracked wall");
+
+    // Assert
+    assert_eq!(result.unwrap(), "The wall crumbles\nThe chisel breaks");
+    let cell = &world.locations["cell"];
+    assert!(cell.has_exit("east"));
+    assert!(cell.has_feature("rubble"));
+    assert!(!cell.has_feature("cracked wall"));
+    assert_eq!(cell.items[0].name, "brick");
+    assert!(!world.player.has_item("chisel"));
+    assert!(world.flags.is_set("wall broken"));
+}
+
+#[test]
+fn test_use_item_without_rule_is_refused() {
+    // Arrange
+    let json = r#"{
+        "start": "cell",
+        "player": { "name": "Alice", "inventory": [{ "name": "spoon" }] },
+        "rooms": [
+            { "id": "cell", "description": "A cell", "features": [{ "name": "cracked wall" }] }
+        ]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let result = world.use_item("use", "spoon", "cracked wall");
+
+    // Assert
+    assert_eq!(result.unwrap_err().to_string(), "You cannot do that here");
+    assert!(world.locations["cell"].has_feature("cracked wall"));
+}
+
+#[test]
+fn test_locked_and_conditional_exits() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "brass key" }] },
+        "rooms": [
+            {
+                "id": "hall",
+                "description": "A hall",
+                "features": [{ "name": "iron gate" }],
+                "exits": {
+                    "north": { "destination": "yard", "locked": true, "key": "brass key",
+                               "door": "iron gate", "refusal": "The gate is locked" },
+                    "down": { "destination": "cellar", "conditions": [{ "has_item": "lamp" }] }
+                }
+            },
+            { "id": "yard", "description": "A yard", "exits": { "south": "hall" } },
+            { "id": "cellar", "description": "A cellar", "exits": { "up": "hall" } }
+        ]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let locked = world.move_player("north");
+    let dark = world.move_player("down");
+    let unlocked = world.use_item("use", "brass key", "iron gate");
+    let moved = world.move_player("north");
+
+    // Assert
+    assert_eq!(locked.unwrap_err().to_string(), "The gate is locked");
+    assert_eq!(dark.unwrap_err().to_string(), "You cannot go down yet");
+    assert_eq!(
+        unlocked.unwrap(),
+        "You unlock the iron gate with the brass key"
+    );
+    assert!(moved.is_ok());
+    assert_eq!(world.player_location, "yard");
+}
+
+#[test]
+fn test_drop_and_put_items_survive_save_round_trip() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": {
+            "name": "Alice",
+            "inventory": [{ "name": "coin" }, { "name": "lamp" }, { "name": "bag", "container": {} }]
+        },
+        "rooms": [{
+            "id": "hall",
+            "description": "A hall",
+            "features": [{ "name": "chest", "container": {} }, { "name": "statue" }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let put_in_chest = world.put_item("coin", "chest");
+    let put_in_statue = world.put_item("lamp", "statue");
+    let put_in_bag = world.put_item("lamp", "bag");
+    let dropped = world.drop_item("bag");
+    let saved = serde_json::to_string(&world).unwrap();
+    let loaded: World = serde_json::from_str(&saved).unwrap();
+
+    // Assert
+    assert_eq!(put_in_chest.unwrap(), "You put the coin in the chest");
+    assert_eq!(
+        put_in_statue.unwrap_err().to_string(),
+        "You cannot put things in the statue"
+    );
+    assert!(put_in_bag.is_ok());
+    assert_eq!(dropped.unwrap(), "Dropped bag");
+    assert!(loaded.player.inventory.is_empty());
+    let hall = &loaded.locations["hall"];
+    let chest = hall.features[0].container.as_ref().unwrap();
+    assert_eq!(chest.contents[0].name, "coin");
+    let bag = hall.items[0].container.as_ref().unwrap();
+    assert_eq!(bag.contents[0].name, "lamp");
+}
+
+#[test]
+fn test_examine_uses_conditional_descriptions_and_lists_contents() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "lamp" }] },
+        "rooms": [{
+            "id": "hall",
+            "description": "A hall",
+            "features": [{
+                "name": "chest",
+                "description": "A closed chest",
+                "alternative_descriptions": [{ "when": [{ "flag_set": "chest open" }], "text": "An open chest" }],
+                "container": { "contents": [{ "name": "coin" }] }
+            }]
+        }],
+        "flags": { "chest open": true }
+    }"#;
+    let world = World::from_json(json).unwrap();
+
+    // Act
+    let chest = world.examine("chest");
+    let lamp = world.examine("lamp");
+    let missing = world.examine("dragon");
+
+    // Assert
+    assert_eq!(chest.unwrap(), "An open chest\nIt contains coin");
+    assert_eq!(lamp.unwrap(), "You see nothing special about the lamp");
+    assert_eq!(missing.unwrap_err().to_string(), "There is no dragon here");
+}
+
+#[test]
+fn test_containers_open_lock_and_fill_up() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "brass key" }, { "name": "gem" }] },
+        "rooms": [{
+            "id": "hall",
+            "description": "A hall",
+            "features": [{
+                "name": "chest",
+                "container": { "contents": [{ "name": "coin" }], "open": false, "openable": true,
+                               "locked": true, "key": "brass key", "capacity": 1 }
+            }],
+            "items": [{ "name": "jar", "container": { "open": false, "transparent": true,
+                                                      "contents": [{ "name": "moth" }] } }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let locked = world.open("chest");
+    let unlocked = world.use_item("unlock", "brass key", "chest");
+    let opened = world.open("chest");
+    let full = world.put_item("gem", "chest");
+    let taken = world.take_item("coin");
+    let empty = world.look_in("chest");
+    let jar = world.look_in("jar");
+    let closed_jar = world.take_from("moth", "jar");
+
+    // Assert
+    assert_eq!(locked.unwrap_err().to_string(), "The chest is locked");
+    assert_eq!(unlocked.unwrap(), "You unlock the chest with the brass key");
+    assert_eq!(opened.unwrap(), "You open the chest, revealing coin");
+    assert_eq!(full.unwrap_err().to_string(), "The chest is full");
+    assert!(world.player.has_item("gem"));
+    assert_eq!(taken.unwrap(), "You take the coin from the chest");
+    assert_eq!(empty.unwrap(), "The chest is empty");
+    assert_eq!(jar.unwrap(), "The jar contains moth");
+    assert_eq!(closed_jar.unwrap_err().to_string(), "The jar is closed");
+}
+
+#[test]
+fn test_take_refuses_items_beyond_carrying_capacity() {
+    // Arrange
+    let json = r#"{
+        "start": "forge",
+        "player": {
+            "name": "Alice",
+            "max_weight": 10,
+            "max_bulk": 3,
+            "inventory": [{ "name": "sack", "weight": 2, "container": { "contents": [{ "name": "ingot", "weight": 5 }] } }]
+        },
+        "rooms": [{
+            "id": "forge",
+            "description": "A forge",
+            "items": [{ "name": "anvil", "weight": 50 }, { "name": "tongs" }, { "name": "bellows", "bulk": 2 }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let anvil = world.take_item("anvil");
+    let tongs = world.take_item("tongs");
+    let bellows = world.take_item("bellows");
+    let ingot = world.take_from("ingot", "sack");
+
+    // Assert
+    assert_eq!(
+        anvil.unwrap_err().to_string(),
+        "The anvil is too heavy to carry as well as everything else"
+    );
+    assert_eq!(tongs.unwrap(), "Picked up tongs");
+    assert_eq!(
+        bellows.unwrap_err().to_string(),
+        "Your hands are full, you cannot carry the bellows as well"
+    );
+    assert!(ingot.is_ok());
+    assert_eq!(
+        world.player.list_inventory(),
+        "sack\ntongs\ningot\nWeight: 8 of 10\nBulk: 3 of 3\n"
+    );
+    assert!(world.locations["forge"]
+        .items
+        .iter()
+        .any(|i| i.id == "anvil"));
+}
+
+#[test]
+fn test_fixed_scenery_and_hidden_objects() {
+    // Arrange
+    let json = r#"{
+        "start": "garden",
+        "player": { "name": "Alice", "inventory": [{ "name": "spade" }] },
+        "rooms": [{
+            "id": "garden",
+            "description": "A garden with a stone bench",
+            "features": [
+                { "name": "statue", "refusal": "The statue is far too heavy" },
+                { "name": "flower bed" }
+            ],
+            "items": [
+                { "name": "bench", "scenery": true },
+                { "name": "sundial", "fixed": true },
+                { "name": "ring", "hidden": true }
+            ]
+        }],
+        "rules": [{
+            "item": "spade",
+            "feature": "flower bed",
+            "message": "You dig up the flower bed",
+            "effects": [{ "reveal": "ring" }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let description = world.locations["garden"].get_full_description(&world);
+    let statue = world.take_item("statue");
+    let flower_bed = world.take_item("flower bed");
+    let sundial = world.take_item("sundial");
+    let bench = world.examine("bench");
+    let hidden_ring = world.take_item("ring");
+    world.use_item("use", "spade", "flower bed").unwrap();
+    let ring = world.take_item("ring");
+
+    // Assert
+    assert!(description.ends_with("There is statue, flower bed\nItems are sundial"));
+    assert_eq!(
+        statue.unwrap_err().to_string(),
+        "The statue is far too heavy"
+    );
+    assert_eq!(
+        flower_bed.unwrap_err().to_string(),
+        "You cannot take the flower bed"
+    );
+    assert_eq!(
+        sundial.unwrap_err().to_string(),
+        "You cannot take the sundial"
+    );
+    assert_eq!(bench.unwrap(), "You see nothing special about the bench");
+    assert_eq!(
+        hidden_ring.unwrap_err().to_string(),
+        "There is no ring here"
+    );
+    assert_eq!(ring.unwrap(), "Picked up ring");
+}
+
+#[test]
+fn test_take_from_weighs_items_already_carried_only_once() {
+    // Arrange
+    let json = r#"{
+        "start": "forge",
+        "player": {
+            "name": "Alice",
+            "max_weight": 6,
+            "inventory": [{ "name": "sack", "container": { "contents": [{ "name": "ingot", "weight": 5 }] } }]
+        },
+        "rooms": [{
+            "id": "forge",
+            "description": "A forge",
+            "features": [{ "name": "crate", "container": { "contents": [{ "name": "hammer", "weight": 2 }] } }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let ingot = world.take_from("ingot", "sack");
+    let hammer = world.take_from("hammer", "crate");
+
+    // Assert
+    assert_eq!(ingot.unwrap(), "You take the ingot from the sack");
+    assert_eq!(
+        hammer.unwrap_err().to_string(),
+        "The hammer is too heavy to carry as well as everything else"
+    );
+    let crate_contents = &world.locations["forge"].features[0]
+        .container
+        .as_ref()
+        .unwrap()
+        .contents;
+    assert_eq!(crate_contents[0].name, "hammer");
+}
+
+#[test]
+fn test_take_does_not_reach_into_closed_containers() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "rooms": [{
+            "id": "hall",
+            "description": "A hall",
+            "features": [{
+                "name": "chest",
+                "container": { "contents": [{ "name": "coin" }], "open": false, "openable": true }
+            }]
+        }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let closed = world.take_item("coin");
+    world.open("chest").unwrap();
+    let opened = world.take_item("coin");
+
+    // Assert
+    assert_eq!(closed.unwrap_err().to_string(), "There is no coin here");
+    assert_eq!(opened.unwrap(), "You take the coin from the chest");
+}
+
+#[test]
+fn test_hidden_items_inside_containers_stay_out_of_sight_until_revealed() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "player": { "name": "Alice", "inventory": [{ "name": "wand" }] },
+        "rooms": [{
+            "id": "hall",
+            "description": "A hall",
+            "features": [{ "name": "mirror" }],
+            "items": [{
+                "name": "box",
+                "container": {
+                    "contents": [{ "name": "gem", "hidden": true }, { "name": "coin" }],
+                    "open": false,
+                    "openable": true
+                }
+            }]
+        }],
+        "rules": [{ "item": "wand", "feature": "mirror", "message": "The mirror flashes",
+                    "effects": [{ "reveal": "gem" }] }]
+    }"#;
+    let mut world = World::from_json(json).unwrap();
+
+    // Act
+    let opened = world.open("box");
+    let looked = world.look_in("box");
+    let hidden = world.take_from("gem", "box");
+    let description = world.locations["hall"].get_full_description(&world);
+    world.use_item("use", "wand", "mirror").unwrap();
+    let revealed = world.take_item("gem");
+
+    // Assert
+    assert_eq!(opened.unwrap(), "You open the box, revealing coin");
+    assert_eq!(looked.unwrap(), "The box contains coin");
+    assert_eq!(
+        hidden.unwrap_err().to_string(),
+        "There is no gem in the box"
+    );
+    assert!(description.ends_with("The box contains coin"));
+    assert_eq!(revealed.unwrap(), "You take the gem from the box");
+}
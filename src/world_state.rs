@@ -0,0 +1,332 @@
+#![warn(missing_docs)]
+
+//! The `world_state` module records the parts of a `World` that change
+//! during play, relative to the world definition, so that saves hold only
+//! what the player has done and pick up fixes made to the definition.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::entities::interactive::Effect;
+use crate::entities::{Container, Exit, Feature, Item, Properties};
+use crate::errors::{GameError, GameResult};
+use crate::flags::Flags;
+use crate::world_building::World;
+
+/// The changes made to a world during play
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldState {
+    /// The id of the room the player is in
+    pub player_location: String,
+    /// The number of turns the player has taken
+    #[serde(default)]
+    pub turns: u32,
+    /// The world's flags
+    #[serde(default)]
+    pub flags: Flags,
+    /// The items the player is carrying
+    #[serde(default)]
+    pub inventory: Vec<ObjectState>,
+    /// The rooms that differ from the definition, stored by Room.id
+    #[serde(default)]
+    pub rooms: HashMap<String, RoomState>,
+}
+
+/// An item or feature, recorded by id along with anything inside it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectState {
+    /// The id of the item or feature in the world definition
+    pub id: String,
+    /// The items inside it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contents: Vec<ObjectState>,
+    /// Whether it is open, if it is a container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open: Option<bool>,
+    /// Whether it is locked, if it is a container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    /// Whether it is still hidden from the player
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hidden: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl ObjectState {
+    fn new(id: &str, properties: &Properties, container: &Option<Container>) -> ObjectState {
+        ObjectState {
+            id: id.to_string(),
+            contents: container
+                .as_ref()
+                .map_or_else(Vec::new, |c| objects(&c.contents)),
+            open: container.as_ref().map(|c| c.open),
+            locked: container.as_ref().map(|c| c.locked),
+            hidden: properties.hidden,
+        }
+    }
+
+    /// Gives an item or feature from the definition the contents and state
+    /// recorded here
+    fn restore(
+        &self,
+        properties: &mut Properties,
+        container: &mut Option<Container>,
+        catalogue: &Catalogue,
+    ) {
+        properties.hidden = self.hidden;
+        if let Some(container) = container {
+            container.contents = catalogue.items(&self.contents);
+            container.open = self.open.unwrap_or(container.open);
+            container.locked = self.locked.unwrap_or(container.locked);
+        }
+    }
+}
+
+/// The parts of a room that differ from the definition
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoomState {
+    /// The items in the room, if they have changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<ObjectState>>,
+    /// The features of the room, if they have changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<ObjectState>>,
+    /// The exits that have been opened, locked or unlocked, by direction
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub exits: HashMap<String, ExitState>,
+}
+
+/// An exit that differs from the definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitState {
+    /// The id of the Room the exit leads to
+    pub destination: String,
+    /// Whether the exit is locked
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl RoomState {
+    fn is_empty(&self) -> bool {
+        self.items.is_none() && self.features.is_none() && self.exits.is_empty()
+    }
+}
+
+impl WorldState {
+    /// Record how a world differs from the definition it was built from
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - the world being played
+    /// * `definition` - the world as it was loaded from its definition
+    pub fn capture(world: &World, definition: &World) -> WorldState {
+        let mut rooms = HashMap::new();
+
+        for (room_id, room) in &world.locations {
+            let original = definition.locations.get(room_id);
+            let mut state = RoomState::default();
+
+            let items = objects(&room.items);
+            if original.map(|r| objects(&r.items)) != Some(items.clone()) {
+                state.items = Some(items);
+            }
+            let features = features(&room.features);
+            if original.map(|r| self::features(&r.features)) != Some(features.clone()) {
+                state.features = Some(features);
+            }
+            for (direction, exit) in &room.exits {
+                let unchanged = original
+                    .and_then(|r| r.exits.get(direction))
+                    .is_some_and(|o| o.destination == exit.destination && o.locked == exit.locked);
+                if !unchanged {
+                    state.exits.insert(
+                        direction.clone(),
+                        ExitState {
+                            destination: exit.destination.clone(),
+                            locked: exit.locked,
+                        },
+                    );
+                }
+            }
+
+            if !state.is_empty() {
+                rooms.insert(room_id.clone(), state);
+            }
+        }
+
+        WorldState {
+            player_location: world.player_location.clone(),
+            turns: world.turns,
+            flags: world.flags.clone(),
+            inventory: objects(&world.player.inventory),
+            rooms,
+        }
+    }
+
+    /// Rebuild a world by applying the recorded changes to its definition.
+    /// Items, features and rooms the definition no longer has are skipped,
+    /// as are exits leading to them.
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - the world as it was loaded from its definition
+    ///
+    /// # Errors
+    /// The player is in a room the definition no longer has
+    pub fn apply(&self, definition: &World) -> GameResult<World> {
+        if !definition.locations.contains_key(&self.player_location) {
+            return Err(GameError::MissingRoom(self.player_location.clone()));
+        }
+
+        let catalogue = Catalogue::new(definition);
+        let mut world = definition.clone();
+        world.player_location = self.player_location.clone();
+        world.turns = self.turns;
+        world.flags = self.flags.clone();
+        world.player.inventory = catalogue.items(&self.inventory);
+
+        for (room_id, state) in &self.rooms {
+            let room = match world.locations.get_mut(room_id) {
+                Some(room) => room,
+                None => {
+                    warn!("Skipping saved room '{}' which no longer exists", room_id);
+                    continue;
+                }
+            };
+            if let Some(items) = &state.items {
+                room.items = catalogue.items(items);
+            }
+            if let Some(features) = &state.features {
+                room.features = catalogue.features(features);
+            }
+            for (direction, exit_state) in &state.exits {
+                if !definition.locations.contains_key(&exit_state.destination) {
+                    warn!(
+                        "Skipping saved exit '{}' of room '{}' to '{}' which no longer exists",
+                        direction, room_id, exit_state.destination
+                    );
+                    continue;
+                }
+                let exit = room
+                    .exits
+                    .entry(direction.clone())
+                    .or_insert_with(|| Exit::new(exit_state.destination.clone()));
+                exit.destination = exit_state.destination.clone();
+                exit.locked = exit_state.locked;
+            }
+        }
+
+        Ok(world)
+    }
+}
+
+fn objects(items: &[Item]) -> Vec<ObjectState> {
+    items
+        .iter()
+        .map(|item| ObjectState::new(&item.id, &item.properties, &item.container))
+        .collect()
+}
+
+fn features(features: &[Feature]) -> Vec<ObjectState> {
+    features
+        .iter()
+        .map(|feature| ObjectState::new(&feature.id, &feature.properties, &feature.container))
+        .collect()
+}
+
+/// Every item and feature a world definition can produce, stored by id and
+/// emptied of contents, used to turn saved ids back into objects
+struct Catalogue {
+    items: HashMap<String, Item>,
+    features: HashMap<String, Feature>,
+}
+
+impl Catalogue {
+    fn new(definition: &World) -> Catalogue {
+        let mut catalogue = Catalogue {
+            items: HashMap::new(),
+            features: HashMap::new(),
+        };
+
+        catalogue.add_items(&definition.player.inventory);
+        for room in definition.locations.values() {
+            catalogue.add_items(&room.items);
+            for feature in &room.features {
+                catalogue.add_feature(feature);
+            }
+        }
+        for effect in definition.rules.iter().flat_map(|rule| &rule.effects) {
+            match effect {
+                Effect::SpawnItem(item) => catalogue.add_items(std::slice::from_ref(item)),
+                Effect::ReplaceFeature { with, .. } => catalogue.add_feature(with),
+                _ => {}
+            }
+        }
+
+        catalogue
+    }
+
+    fn add_items(&mut self, items: &[Item]) {
+        for item in items {
+            let mut template = item.clone();
+            if let Some(container) = &mut template.container {
+                self.add_items(&container.contents);
+                container.contents.clear();
+            }
+            self.items.entry(item.id.clone()).or_insert(template);
+        }
+    }
+
+    fn add_feature(&mut self, feature: &Feature) {
+        let mut template = feature.clone();
+        if let Some(container) = &mut template.container {
+            self.add_items(&container.contents);
+            container.contents.clear();
+        }
+        self.features.entry(feature.id.clone()).or_insert(template);
+    }
+
+    fn items(&self, states: &[ObjectState]) -> Vec<Item> {
+        states
+            .iter()
+            .filter_map(|state| match self.items.get(&state.id) {
+                Some(template) => {
+                    let mut item = template.clone();
+                    state.restore(&mut item.properties, &mut item.container, self);
+                    Some(item)
+                }
+                None => {
+                    warn!("Skipping saved item '{}' which no longer exists", state.id);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn features(&self, states: &[ObjectState]) -> Vec<Feature> {
+        states
+            .iter()
+            .filter_map(|state| match self.features.get(&state.id) {
+                Some(template) => {
+                    let mut feature = template.clone();
+                    state.restore(&mut feature.properties, &mut feature.container, self);
+                    Some(feature)
+                }
+                None => {
+                    warn!(
+                        "Skipping saved feature '{}' which no longer exists",
+                        state.id
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "./world_state_tests.rs"]
+mod world_state_tests;
@@ -0,0 +1,154 @@
+use super::{ExitState, WorldState};
+use crate::errors::GameError;
+use crate::world_building::World;
+use crate::DEFAULT_WORLD;
+
+#[test]
+fn test_capture_records_only_changes() {
+    // Arrange
+    let definition = World::from_file(DEFAULT_WORLD).unwrap();
+    let mut world = definition.clone();
+    world.player_location = "corridor".to_string();
+    world.take_item("key").unwrap_err();
+    world.player_location = "storeroom".to_string();
+    world.take_item("key").unwrap();
+    world.player_location = "corridor".to_string();
+    world.use_item("use", "key", "north door").unwrap();
+
+    // Act
+    let state = WorldState::capture(&world, &definition);
+
+    // Assert
+    assert_eq!(state.player_location, "corridor");
+    assert_eq!(state.inventory.len(), 1);
+    assert_eq!(state.inventory[0].id, "key");
+    let mut changed: Vec<&String> = state.rooms.keys().collect();
+    changed.sort();
+    assert_eq!(changed, vec!["corridor", "storeroom"]);
+    assert!(!state.rooms["corridor"].exits["north"].locked);
+    assert!(state.rooms["corridor"].items.is_none());
+    assert_eq!(state.rooms["storeroom"].items.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn test_apply_rebuilds_the_world_from_a_fixed_definition() {
+    // Arrange
+    let definition = World::from_file(DEFAULT_WORLD).unwrap();
+    let mut world = definition.clone();
+    world.player_location = "storeroom".to_string();
+    world.take_item("tinderbox").unwrap();
+    world.turns = 4;
+    let state = WorldState::capture(&world, &definition);
+    let mut fixed = definition.clone();
+    for item in fixed
+        .locations
+        .values_mut()
+        .flat_map(|room| &mut room.items)
+    {
+        item.description = Some("Fixed".to_string());
+    }
+
+    // Act
+    let restored = state.apply(&fixed).unwrap();
+
+    // Assert
+    assert_eq!(restored.player_location, "storeroom");
+    assert_eq!(restored.turns, 4);
+    assert_eq!(restored.player.inventory[0].name, "tinderbox");
+    assert_eq!(
+        restored.player.inventory[0].description.as_deref(),
+        Some("Fixed")
+    );
+    assert_eq!(restored.locations["storeroom"].items.len(), 1);
+}
+
+#[test]
+fn test_apply_refuses_missing_location() {
+    let definition = World::from_file(DEFAULT_WORLD).unwrap();
+    let mut state = WorldState::capture(&definition, &definition);
+    state.player_location = "attic".to_string();
+
+    assert_eq!(
+        state.apply(&definition).unwrap_err(),
+        GameError::MissingRoom("attic".to_string())
+    );
+}
+
+#[test]
+fn test_container_state_survives_capture_and_apply() {
+    // Arrange
+    let definition = World::from_json(
+        r#"{
+            "start": "hall",
+            "rooms": [{
+                "id": "hall",
+                "description": "A hall",
+                "features": [{ "name": "chest", "container": { "open": false, "openable": true } }]
+            }]
+        }"#,
+    )
+    .unwrap();
+    let mut world = definition.clone();
+    world.open("chest").unwrap();
+
+    // Act
+    let state = WorldState::capture(&world, &definition);
+    let restored = state.apply(&definition).unwrap();
+
+    // Assert
+    let chest = restored.locations["hall"].features[0].container.as_ref();
+    assert!(chest.unwrap().open);
+}
+
+#[test]
+fn test_revealed_objects_stay_revealed_after_apply() {
+    // Arrange
+    let definition = World::from_json(
+        r#"{
+            "start": "hall",
+            "rooms": [{
+                "id": "hall",
+                "description": "A hall",
+                "items": [{ "name": "ring", "hidden": true }, { "name": "note", "hidden": true }]
+            }]
+        }"#,
+    )
+    .unwrap();
+    let mut world = definition.clone();
+    world.current_room_mut().unwrap().reveal("ring").unwrap();
+
+    // Act
+    let state = WorldState::capture(&world, &definition);
+    let restored = state.apply(&definition).unwrap();
+
+    // Assert
+    let items = &restored.locations["hall"].items;
+    assert!(!items[0].properties.hidden);
+    assert!(items[1].properties.hidden);
+}
+
+#[test]
+fn test_apply_skips_exits_to_missing_rooms() {
+    // Arrange
+    let definition = World::from_file(DEFAULT_WORLD).unwrap();
+    let mut state = WorldState::capture(&definition, &definition);
+    state
+        .rooms
+        .entry("entrance".to_string())
+        .or_default()
+        .exits
+        .insert(
+            "up".to_string(),
+            ExitState {
+                destination: "hello".to_string(),
+                locked: false,
+            },
+        );
+
+    // Act
+    let restored = state.apply(&definition).unwrap();
+
+    // Assert
+    assert!(!restored.locations["entrance"].has_exit("up"));
+    assert!(restored.validate().is_empty());
+}
@@ -0,0 +1,283 @@
+#![warn(missing_docs)]
+
+//! The `world_validation` module checks a `World` for mistakes a content
+//! author can make, such as exits that lead nowhere.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::entities::interactive::{Effect, Rule};
+use crate::entities::Item;
+use crate::vocabulary::VERBS;
+use crate::world_building::World;
+
+/// A problem found in a world
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    /// The player starts in a room that does not exist
+    UnknownStart {
+        /// The id of the missing room
+        location: String,
+    },
+    /// An exit leads to a room that does not exist
+    DanglingExit {
+        /// The id of the room the exit leaves from
+        room: String,
+        /// The direction of the exit
+        direction: String,
+        /// The id of the missing room
+        destination: String,
+    },
+    /// No path of exits leads from the player's location to the room
+    UnreachableRoom {
+        /// The id of the unreachable room
+        room: String,
+        /// The id of the room the search started from
+        from: String,
+    },
+    /// More than one room was defined with the same id
+    DuplicateRoom {
+        /// The id shared by the rooms
+        room: String,
+    },
+    /// An interaction rule refers to a room that does not exist
+    InvalidRule {
+        /// A description of the rule
+        rule: String,
+        /// The id of the missing room
+        room: String,
+    },
+    /// Two items or features share an id, which rules, saves and commands
+    /// use to tell them apart
+    DuplicateId {
+        /// The shared id
+        id: String,
+        /// Where the first object with the id is
+        first: String,
+        /// Where the second object with the id is
+        second: String,
+    },
+    /// The vocabulary gives words for a verb the parser does not know
+    UnknownVerb {
+        /// The unknown verb
+        verb: String,
+    },
+}
+
+impl ValidationError {
+    /// Returns true if the world can still be played despite the problem
+    pub fn is_warning(&self) -> bool {
+        matches!(self, ValidationError::UnreachableRoom { .. })
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::UnknownStart { location } => {
+                write!(f, "starting location '{}' is not a room", location)
+            }
+            ValidationError::DanglingExit {
+                room,
+                direction,
+                destination,
+            } => write!(
+                f,
+                "room '{}': exit '{}' leads to unknown room '{}'",
+                room, direction, destination
+            ),
+            ValidationError::UnreachableRoom { room, from } => {
+                write!(f, "room '{}' cannot be reached from '{}'", room, from)
+            }
+            ValidationError::DuplicateRoom { room } => {
+                write!(f, "room '{}' is defined more than once", room)
+            }
+            ValidationError::InvalidRule { rule, room } => {
+                write!(f, "{}: room '{}' does not exist", rule, room)
+            }
+            ValidationError::DuplicateId { id, first, second } => write!(
+                f,
+                "{}: id '{}' is already used in {}, give one of them its own id",
+                second, id, first
+            ),
+            ValidationError::UnknownVerb { verb } => write!(
+                f,
+                "vocabulary: '{}' is not a verb, use one of {} or a verb from a rule",
+                verb,
+                VERBS.join(", ")
+            ),
+        }
+    }
+}
+
+impl World {
+    /// Check the world for exits to missing rooms, rooms the player cannot
+    /// reach, objects sharing an id and an unknown player location.
+    ///
+    /// Rooms are stored by id so a `World` cannot hold duplicates; those are
+    /// reported when the world definition is loaded.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if !self.locations.contains_key(&self.player_location) {
+            errors.push(ValidationError::UnknownStart {
+                location: self.player_location.clone(),
+            });
+        }
+
+        let mut room_ids: Vec<&String> = self.locations.keys().collect();
+        room_ids.sort();
+
+        for room_id in &room_ids {
+            let room = &self.locations[*room_id];
+            let mut directions: Vec<&String> = room.get_exits().collect();
+            directions.sort();
+            for direction in directions {
+                let destination = &room.exits[direction].destination;
+                if !self.locations.contains_key(destination) {
+                    errors.push(ValidationError::DanglingExit {
+                        room: room.id.clone(),
+                        direction: direction.clone(),
+                        destination: destination.clone(),
+                    });
+                }
+            }
+        }
+
+        for rule in &self.rules {
+            for room_id in rule.room.iter().chain(rule_destinations(rule)) {
+                if !self.locations.contains_key(room_id) {
+                    errors.push(ValidationError::InvalidRule {
+                        rule: rule.label(),
+                        room: room_id.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut places: HashMap<&str, String> = HashMap::new();
+        for (id, place) in self.object_ids(&room_ids) {
+            match places.get(id) {
+                Some(first) => errors.push(ValidationError::DuplicateId {
+                    id: id.to_string(),
+                    first: first.clone(),
+                    second: place,
+                }),
+                None => {
+                    places.insert(id, place);
+                }
+            }
+        }
+
+        let rule_verbs: Vec<&String> = self.rules.iter().flat_map(|rule| &rule.verbs).collect();
+        for verb in self.vocabulary.verbs.keys() {
+            if !VERBS.contains(&verb.as_str())
+                && !rule_verbs
+                    .iter()
+                    .any(|rule_verb| rule_verb.eq_ignore_ascii_case(verb))
+            {
+                errors.push(ValidationError::UnknownVerb { verb: verb.clone() });
+            }
+        }
+
+        // Reachability means nothing without somewhere to start from
+        if self.locations.contains_key(&self.player_location) {
+            let reachable = self.reachable_rooms();
+            for room_id in room_ids {
+                if !reachable.contains(room_id) {
+                    errors.push(ValidationError::UnreachableRoom {
+                        room: room_id.clone(),
+                        from: self.player_location.clone(),
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// The ids of every item and feature the world places or its rules
+    /// create, including those inside containers, along with where each is
+    fn object_ids<'a>(&'a self, room_ids: &[&'a String]) -> Vec<(&'a str, String)> {
+        let mut ids = Vec::new();
+        for room_id in room_ids {
+            let room = &self.locations[*room_id];
+            let place = format!("room '{}'", room_id);
+            for feature in &room.features {
+                ids.push((feature.id.as_str(), place.clone()));
+                if let Some(container) = &feature.container {
+                    item_ids(&container.contents, &place, &mut ids);
+                }
+            }
+            item_ids(&room.items, &place, &mut ids);
+        }
+        item_ids(&self.player.inventory, "the player's inventory", &mut ids);
+        for rule in &self.rules {
+            for effect in &rule.effects {
+                match effect {
+                    Effect::SpawnItem(item) => {
+                        item_ids(std::slice::from_ref(item), &rule.label(), &mut ids)
+                    }
+                    Effect::ReplaceFeature { with, .. } => {
+                        ids.push((with.id.as_str(), rule.label()));
+                        if let Some(container) = &with.container {
+                            item_ids(&container.contents, &rule.label(), &mut ids);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        ids
+    }
+
+    /// Walks the exits outward from the player's location, including exits
+    /// that are locked or conditional and exits that rules may open. Exits
+    /// opened by rules that apply in any room are assumed to be reachable.
+    fn reachable_rooms(&self) -> HashSet<&String> {
+        let mut reachable = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(&self.player_location);
+        for rule in self.rules.iter().filter(|rule| rule.room.is_none()) {
+            queue.extend(rule_destinations(rule));
+        }
+
+        while let Some(room_id) = queue.pop_front() {
+            if !reachable.insert(room_id) {
+                continue;
+            }
+            if let Some(room) = self.locations.get(room_id) {
+                queue.extend(room.exits.values().map(|exit| &exit.destination));
+            }
+            for rule in &self.rules {
+                if rule.room.as_ref() == Some(room_id) {
+                    queue.extend(rule_destinations(rule));
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+/// Adds the ids of items, and of anything inside them, found in a place
+fn item_ids<'a>(items: &'a [Item], place: &str, ids: &mut Vec<(&'a str, String)>) {
+    for item in items {
+        ids.push((item.id.as_str(), place.to_string()));
+        if let Some(container) = &item.container {
+            item_ids(&container.contents, place, ids);
+        }
+    }
+}
+
+/// The ids of the rooms a rule can open exits to
+fn rule_destinations(rule: &Rule) -> impl Iterator<Item = &String> {
+    rule.effects.iter().filter_map(|effect| match effect {
+        Effect::OpenExit { destination, .. } => Some(destination),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+#[path = "./world_validation_tests.rs"]
+mod world_validation_tests;
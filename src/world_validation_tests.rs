@@ -0,0 +1,211 @@
+use super::ValidationError;
+use crate::entities::Room;
+use crate::world_building::World;
+
+fn room(id: &str, exits: &[(&str, &str)]) -> Room {
+    let mut room = Room::new(id.to_string(), "description".to_string());
+    for (direction, destination) in exits {
+        room.add_exit(direction.to_string(), destination.to_string());
+    }
+    room
+}
+
+fn world(player_location: &str, rooms: Vec<Room>) -> World {
+    let mut world = World {
+        player_location: player_location.to_string(),
+        ..World::default()
+    };
+    for room in rooms {
+        world.add_location(room);
+    }
+    world
+}
+
+#[test]
+fn test_validate_connected_world() {
+    // Arrange
+    let world = world(
+        "hall",
+        vec![
+            room("hall", &[("east", "kitchen")]),
+            room("kitchen", &[("west", "hall")]),
+        ],
+    );
+
+    // Act
+    let result = world.validate();
+
+    // Assert
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_validate_dangling_exit_and_unreachable_room() {
+    // Arrange
+    let world = world(
+        "hall",
+        vec![
+            room("hall", &[("up", "hello")]),
+            room("cellar", &[("up", "hall")]),
+        ],
+    );
+
+    // Act
+    let result = world.validate();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![
+            ValidationError::DanglingExit {
+                room: "hall".to_string(),
+                direction: "up".to_string(),
+                destination: "hello".to_string(),
+            },
+            ValidationError::UnreachableRoom {
+                room: "cellar".to_string(),
+                from: "hall".to_string(),
+            },
+        ]
+    );
+    assert!(!result[0].is_warning());
+    assert!(result[1].is_warning());
+}
+
+#[test]
+fn test_validate_unknown_start() {
+    // Arrange
+    let world = world("nowhere", vec![room("hall", &[])]);
+
+    // Act
+    let result = world.validate();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![ValidationError::UnknownStart {
+            location: "nowhere".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_check_json_reports_duplicate_rooms() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "rooms": [
+            { "id": "hall", "description": "A hall" },
+            { "id": "hall", "description": "Another hall" }
+        ]
+    }"#;
+
+    // Act
+    let result = World::check_json(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![ValidationError::DuplicateRoom {
+            room: "hall".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_check_json_reports_unknown_verbs() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "rooms": [{ "id": "hall", "description": "A hall", "features": [{ "name": "lamp" }] }],
+        "vocabulary": { "verbs": { "take": ["snatch"], "dance": ["jig"], "light": ["ignite"] } },
+        "rules": [{ "verbs": ["light"], "item": "match", "feature": "lamp", "message": "Lit" }]
+    }"#;
+
+    // Act
+    let result = World::check_json(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![ValidationError::UnknownVerb {
+            verb: "dance".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_shipped_world_is_valid() {
+    let result = World::check_file(crate::DEFAULT_WORLD).unwrap();
+
+    assert_eq!(result, vec![]);
+}
+
+#[test]
+fn test_check_json_follows_and_checks_rule_exits() {
+    // Arrange
+    let json = r#"{
+        "start": "hall",
+        "rooms": [
+            { "id": "hall", "description": "A hall", "features": [{ "name": "door" }] },
+            { "id": "vault", "description": "A vault" }
+        ],
+        "rules": [
+            { "item": "key", "feature": "door", "room": "hall", "message": "Opened",
+              "effects": [{ "open_exit": { "direction": "north", "destination": "vault" } }] },
+            { "item": "key", "feature": "door", "room": "attic", "message": "Opened",
+              "effects": [{ "open_exit": { "direction": "up", "destination": "hello" } }] }
+        ]
+    }"#;
+
+    // Act
+    let result = World::check_json(json).unwrap();
+
+    // Assert
+    let messages: Vec<String> = result.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "rule using 'key' on 'door': room 'attic' does not exist",
+            "rule using 'key' on 'door': room 'hello' does not exist",
+        ]
+    );
+}
+
+#[test]
+fn test_check_json_reports_shared_object_ids() {
+    // Arrange
+    let json = r#"{
+        "start": "a",
+        "player": { "name": "Alice", "inventory": [{ "name": "bag", "container": { "contents": [{ "name": "lamp" }] } }] },
+        "rooms": [
+            { "id": "a", "description": "A", "items": [{ "name": "coin" }], "exits": { "east": "b" } },
+            { "id": "b", "description": "B", "items": [{ "name": "coin", "adjectives": ["gold"] }],
+              "features": [{ "name": "lamp" }], "exits": { "west": "a" } }
+        ]
+    }"#;
+
+    // Act
+    let result = World::check_json(json).unwrap();
+
+    // Assert
+    assert_eq!(
+        result,
+        vec![
+            ValidationError::DuplicateId {
+                id: "coin".to_string(),
+                first: "room 'a'".to_string(),
+                second: "room 'b'".to_string(),
+            },
+            ValidationError::DuplicateId {
+                id: "lamp".to_string(),
+                first: "room 'b'".to_string(),
+                second: "the player's inventory".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        result[0].to_string(),
+        "room 'b': id 'coin' is already used in room 'a', give one of them its own id"
+    );
+}